@@ -0,0 +1,83 @@
+use glium::{Display, Program, Surface, VertexBuffer};
+use glium::index::{NoIndices, PrimitiveType};
+use nalgebra::Point3;
+
+use crate::hud::HudView;
+use crate::model::Model;
+use crate::performance::DrawStats;
+use crate::view::{Viewport, project, view_matrix, world_matrix};
+
+const LINE_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+const POINT_COLOR: [f32; 3] = [1.0, 0.3, 0.8];
+
+/// A colored vertex of the measurement annotations.
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+implement_vertex!(Vertex, position, color);
+
+/// Draws the measurements as 3D annotations: The measured points, the lines between them and a
+/// label with the measured value.
+pub struct AnnotationView {
+    program: Program,
+}
+impl AnnotationView {
+    pub fn new(display: &Display) -> Self {
+        let vertex_shader_src = include_str!("reference.vertex.glsl");
+        let fragment_shader_src = include_str!("reference.fragment.glsl");
+        let program = Program::from_source(display, vertex_shader_src, fragment_shader_src,
+                                           None).unwrap();
+        Self { program }
+    }
+
+    /// Draws all measurements and the points of the measurement in progress. The annotations are
+    /// always drawn on top of the object, so the points on the back stay visible.
+    pub fn draw<S: Surface>(&self, display: &Display, target: &mut S, hud: &mut HudView, model: &Model,
+                            viewport: &Viewport, stats: &mut DrawStats) {
+        if model.measurements.is_empty() && model.measure_points.is_empty() {
+            return;
+        }
+
+        let mut lines = Vec::new();
+        let mut points = Vec::new();
+        for measurement in &model.measurements {
+            let world_points = measurement.world_points(model);
+            for pair in world_points.windows(2) {
+                lines.push(Vertex { position: pair[0].into(), color: LINE_COLOR });
+                lines.push(Vertex { position: pair[1].into(), color: LINE_COLOR });
+            }
+            points.extend(world_points.iter().map(|p| Vertex { position: (*p).into(), color: POINT_COLOR }));
+        }
+        let world = world_matrix(model);
+        for p in &model.measure_points {
+            let position = world.transform_point(&Point3::from(*p)).coords;
+            points.push(Vertex { position: position.into(), color: POINT_COLOR });
+        }
+
+        let view = view_matrix(&model.view_position, &model.view_direction, &model.up);
+        let perspective = viewport.projection(model);
+        let uniforms = uniform! { view: view, perspective: perspective };
+        let params = glium::DrawParameters {
+            line_width: Some(2.0),
+            point_size: Some(6.0),
+            .. Default::default()
+        };
+
+        if !lines.is_empty() {
+            let buffer = VertexBuffer::new(display, &lines).unwrap();
+            target.draw(&buffer, NoIndices(PrimitiveType::LinesList), &self.program, &uniforms, &params).unwrap();
+            stats.count(0);
+        }
+        let buffer = VertexBuffer::new(display, &points).unwrap();
+        target.draw(&buffer, NoIndices(PrimitiveType::Points), &self.program, &uniforms, &params).unwrap();
+        stats.count(0);
+
+        for measurement in &model.measurements {
+            if let Some(screen) = project(model, &measurement.label_position(model), viewport.width, viewport.height) {
+                hud.queue_label(&measurement.label(model), screen);
+            }
+        }
+    }
+}
@@ -0,0 +1,689 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use glium::glutin::event::{ModifiersState, VirtualKeyCode};
+use serde::Deserialize;
+
+use crate::navigation::Navigation;
+
+// The names and the default keys of the bookmarks 1 to 9.
+const RECALL_BOOKMARK_NAMES: [&str; 9] = [
+    "recall_bookmark_1", "recall_bookmark_2", "recall_bookmark_3", "recall_bookmark_4", "recall_bookmark_5",
+    "recall_bookmark_6", "recall_bookmark_7", "recall_bookmark_8", "recall_bookmark_9",
+];
+const STORE_BOOKMARK_NAMES: [&str; 9] = [
+    "store_bookmark_1", "store_bookmark_2", "store_bookmark_3", "store_bookmark_4", "store_bookmark_5",
+    "store_bookmark_6", "store_bookmark_7", "store_bookmark_8", "store_bookmark_9",
+];
+const RECALL_BOOKMARK_KEYS: [[&str; 1]; 9] = [
+    ["Alt+Key1"], ["Alt+Key2"], ["Alt+Key3"], ["Alt+Key4"], ["Alt+Key5"],
+    ["Alt+Key6"], ["Alt+Key7"], ["Alt+Key8"], ["Alt+Key9"],
+];
+const STORE_BOOKMARK_KEYS: [[&str; 1]; 9] = [
+    ["Shift+Alt+Key1"], ["Shift+Alt+Key2"], ["Shift+Alt+Key3"], ["Shift+Alt+Key4"], ["Shift+Alt+Key5"],
+    ["Shift+Alt+Key6"], ["Shift+Alt+Key7"], ["Shift+Alt+Key8"], ["Shift+Alt+Key9"],
+];
+
+/// The actions, which can be bound to keys. The names of the actions are used in the config file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    RotateLeft,
+    RotateRight,
+    RotateUp,
+    RotateDown,
+    RollUp,
+    RollDown,
+    ScaleUp,
+    ScaleDown,
+    MoveXNeg,
+    MoveXPos,
+    MoveYNeg,
+    MoveYPos,
+    MoveZNeg,
+    MoveZPos,
+    ViewUp,
+    ViewDown,
+    ViewLeft,
+    ViewRight,
+    ViewForward,
+    ViewBackward,
+    ResetView,
+    ToggleNavigation,
+    FlyForward,
+    FlyBackward,
+    FlyLeft,
+    FlyRight,
+    FlyUp,
+    FlyDown,
+    ToggleProjection,
+    FrontView,
+    BackView,
+    LeftView,
+    RightView,
+    TopView,
+    BottomView,
+    IsometricView,
+    WidenFieldOfView,
+    NarrowFieldOfView,
+    NearCloser,
+    NearFarther,
+    FarCloser,
+    FarFarther,
+    ToggleAutoClip,
+    /// Recalls the bookmark with the number 1 to 9.
+    RecallBookmark(usize),
+    /// Stores the bookmark with the number 1 to 9.
+    StoreBookmark(usize),
+    SaveSession,
+    Undo,
+    Redo,
+    ResetObject,
+    ToggleGrid,
+    ToggleAxes,
+    ToggleGizmo,
+    ToggleBounds,
+    ToggleOrientedBounds,
+    ToggleShadows,
+    ToggleSoftShadows,
+    ToggleGround,
+    ToggleHelp,
+    ToggleObjectSection,
+    ToggleCameraSection,
+    ToggleDirectionSection,
+    ToggleStatisticsSection,
+    TogglePerformanceSection,
+    ToggleContinuousRendering,
+    ToggleConsole,
+    NextLightPreset,
+    ToggleCameraLight,
+    NextUnit,
+    NextMeasureMode,
+    RemoveMeasurement,
+    ExportMeasurements,
+    ExportImage,
+    Screenshot,
+    Quit,
+}
+impl Action {
+    /// All actions in the order, in which they are presented to the user.
+    pub const ALL: [Action; 90] = [
+        Action::RotateLeft, Action::RotateRight, Action::RotateUp, Action::RotateDown,
+        Action::RollUp, Action::RollDown, Action::ScaleUp, Action::ScaleDown,
+        Action::MoveXNeg, Action::MoveXPos, Action::MoveYNeg, Action::MoveYPos,
+        Action::MoveZNeg, Action::MoveZPos,
+        Action::ViewUp, Action::ViewDown, Action::ViewLeft, Action::ViewRight,
+        Action::ViewForward, Action::ViewBackward, Action::ResetView,
+        Action::ToggleNavigation, Action::FlyForward, Action::FlyBackward, Action::FlyLeft,
+        Action::FlyRight, Action::FlyUp, Action::FlyDown,
+        Action::ToggleProjection, Action::FrontView, Action::BackView, Action::LeftView,
+        Action::RightView, Action::TopView, Action::BottomView, Action::IsometricView,
+        Action::WidenFieldOfView, Action::NarrowFieldOfView, Action::NearCloser, Action::NearFarther,
+        Action::FarCloser, Action::FarFarther, Action::ToggleAutoClip,
+        Action::RecallBookmark(1), Action::RecallBookmark(2), Action::RecallBookmark(3),
+        Action::RecallBookmark(4), Action::RecallBookmark(5), Action::RecallBookmark(6),
+        Action::RecallBookmark(7), Action::RecallBookmark(8), Action::RecallBookmark(9),
+        Action::StoreBookmark(1), Action::StoreBookmark(2), Action::StoreBookmark(3),
+        Action::StoreBookmark(4), Action::StoreBookmark(5), Action::StoreBookmark(6),
+        Action::StoreBookmark(7), Action::StoreBookmark(8), Action::StoreBookmark(9),
+        Action::SaveSession, Action::Undo, Action::Redo, Action::ResetObject,
+        Action::ToggleGrid, Action::ToggleAxes, Action::ToggleGizmo,
+        Action::ToggleBounds, Action::ToggleOrientedBounds, Action::ToggleShadows,
+        Action::ToggleSoftShadows, Action::ToggleGround, Action::ToggleHelp,
+        Action::ToggleObjectSection, Action::ToggleCameraSection, Action::ToggleDirectionSection,
+        Action::ToggleStatisticsSection, Action::TogglePerformanceSection, Action::ToggleContinuousRendering,
+        Action::ToggleConsole, Action::NextLightPreset, Action::ToggleCameraLight,
+        Action::NextUnit,
+        Action::NextMeasureMode, Action::RemoveMeasurement, Action::ExportMeasurements,
+        Action::ExportImage, Action::Screenshot, Action::Quit,
+    ];
+
+    /// The name of the action in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::RotateLeft => "rotate_left",
+            Action::RotateRight => "rotate_right",
+            Action::RotateUp => "rotate_up",
+            Action::RotateDown => "rotate_down",
+            Action::RollUp => "roll_up",
+            Action::RollDown => "roll_down",
+            Action::ScaleUp => "scale_up",
+            Action::ScaleDown => "scale_down",
+            Action::MoveXNeg => "move_x_neg",
+            Action::MoveXPos => "move_x_pos",
+            Action::MoveYNeg => "move_y_neg",
+            Action::MoveYPos => "move_y_pos",
+            Action::MoveZNeg => "move_z_neg",
+            Action::MoveZPos => "move_z_pos",
+            Action::ViewUp => "view_up",
+            Action::ViewDown => "view_down",
+            Action::ViewLeft => "view_left",
+            Action::ViewRight => "view_right",
+            Action::ViewForward => "view_forward",
+            Action::ViewBackward => "view_backward",
+            Action::ResetView => "reset_view",
+            Action::ToggleNavigation => "toggle_navigation",
+            Action::FlyForward => "fly_forward",
+            Action::FlyBackward => "fly_backward",
+            Action::FlyLeft => "fly_left",
+            Action::FlyRight => "fly_right",
+            Action::FlyUp => "fly_up",
+            Action::FlyDown => "fly_down",
+            Action::ToggleProjection => "toggle_projection",
+            Action::FrontView => "front_view",
+            Action::BackView => "back_view",
+            Action::LeftView => "left_view",
+            Action::RightView => "right_view",
+            Action::TopView => "top_view",
+            Action::BottomView => "bottom_view",
+            Action::IsometricView => "isometric_view",
+            Action::WidenFieldOfView => "widen_field_of_view",
+            Action::NarrowFieldOfView => "narrow_field_of_view",
+            Action::NearCloser => "near_closer",
+            Action::NearFarther => "near_farther",
+            Action::FarCloser => "far_closer",
+            Action::FarFarther => "far_farther",
+            Action::ToggleAutoClip => "toggle_auto_clip",
+            Action::RecallBookmark(number) => RECALL_BOOKMARK_NAMES[number - 1],
+            Action::StoreBookmark(number) => STORE_BOOKMARK_NAMES[number - 1],
+            Action::SaveSession => "save_session",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::ResetObject => "reset_object",
+            Action::ToggleGrid => "toggle_grid",
+            Action::ToggleAxes => "toggle_axes",
+            Action::ToggleGizmo => "toggle_gizmo",
+            Action::ToggleBounds => "toggle_bounds",
+            Action::ToggleOrientedBounds => "toggle_oriented_bounds",
+            Action::ToggleShadows => "toggle_shadows",
+            Action::ToggleSoftShadows => "toggle_soft_shadows",
+            Action::ToggleGround => "toggle_ground",
+            Action::ToggleHelp => "toggle_help",
+            Action::ToggleObjectSection => "toggle_object_section",
+            Action::ToggleCameraSection => "toggle_camera_section",
+            Action::ToggleDirectionSection => "toggle_direction_section",
+            Action::ToggleStatisticsSection => "toggle_statistics_section",
+            Action::TogglePerformanceSection => "toggle_performance_section",
+            Action::ToggleContinuousRendering => "toggle_continuous_rendering",
+            Action::ToggleConsole => "toggle_console",
+            Action::NextLightPreset => "next_light_preset",
+            Action::ToggleCameraLight => "toggle_camera_light",
+            Action::NextUnit => "next_unit",
+            Action::NextMeasureMode => "next_measure_mode",
+            Action::RemoveMeasurement => "remove_measurement",
+            Action::ExportMeasurements => "export_measurements",
+            Action::ExportImage => "export_image",
+            Action::Screenshot => "screenshot",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// The description of the action in the help.
+    pub fn description(&self) -> String {
+        let description = match self {
+            Action::RotateLeft => "Rotate the object left",
+            Action::RotateRight => "Rotate the object right",
+            Action::RotateUp => "Rotate the object up",
+            Action::RotateDown => "Rotate the object down",
+            Action::RollUp => "Roll the object up",
+            Action::RollDown => "Roll the object down",
+            Action::ScaleUp => "Enlarge the object",
+            Action::ScaleDown => "Shrink the object",
+            Action::MoveXNeg => "Move the object left",
+            Action::MoveXPos => "Move the object right",
+            Action::MoveYNeg => "Move the object down",
+            Action::MoveYPos => "Move the object up",
+            Action::MoveZNeg => "Move the object closer",
+            Action::MoveZPos => "Move the object away",
+            Action::ViewUp => "Move the viewer up",
+            Action::ViewDown => "Move the viewer down",
+            Action::ViewLeft => "Move the viewer left",
+            Action::ViewRight => "Move the viewer right",
+            Action::ViewForward => "Move the viewer forward",
+            Action::ViewBackward => "Move the viewer backward",
+            Action::ResetView => "Reset the viewer",
+            Action::ToggleNavigation => "Switch between orbit and fly",
+            Action::FlyForward => "Fly forward",
+            Action::FlyBackward => "Fly backward",
+            Action::FlyLeft => "Fly left",
+            Action::FlyRight => "Fly right",
+            Action::FlyUp => "Fly up",
+            Action::FlyDown => "Fly down",
+            Action::ToggleProjection => "Switch perspective and orthographic",
+            Action::FrontView => "Front view",
+            Action::BackView => "Back view",
+            Action::LeftView => "Left view",
+            Action::RightView => "Right view",
+            Action::TopView => "Top view",
+            Action::BottomView => "Bottom view",
+            Action::IsometricView => "Isometric view",
+            Action::WidenFieldOfView => "Widen the field of view",
+            Action::NarrowFieldOfView => "Narrow the field of view",
+            Action::NearCloser => "Move the near plane closer",
+            Action::NearFarther => "Move the near plane away",
+            Action::FarCloser => "Move the far plane closer",
+            Action::FarFarther => "Move the far plane away",
+            Action::ToggleAutoClip => "Fit the clip planes automatically",
+            Action::RecallBookmark(number) => return format!("Recall bookmark {number}"),
+            Action::StoreBookmark(number) => return format!("Store bookmark {number}"),
+            Action::SaveSession => "Save the session",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::ResetObject => "Reset the object",
+            Action::ToggleGrid => "Show the grid",
+            Action::ToggleAxes => "Show the axes",
+            Action::ToggleGizmo => "Show the orientation gizmo",
+            Action::ToggleBounds => "Show the bounding box",
+            Action::ToggleOrientedBounds => "Show the oriented bounding box",
+            Action::ToggleShadows => "Show the shadows",
+            Action::ToggleSoftShadows => "Soften the shadow edges",
+            Action::ToggleGround => "Show the ground plane",
+            Action::ToggleHelp => "Show this help",
+            Action::ToggleObjectSection => "Show the object in the HUD",
+            Action::ToggleCameraSection => "Show the camera in the HUD",
+            Action::ToggleDirectionSection => "Show the direction in the HUD",
+            Action::ToggleStatisticsSection => "Show the mesh statistics in the HUD",
+            Action::TogglePerformanceSection => "Show the frame timing in the HUD",
+            Action::ToggleContinuousRendering => "Render continuously",
+            Action::ToggleConsole => "Open the command console",
+            Action::NextLightPreset => "Next light preset",
+            Action::ToggleCameraLight => "Attach the key light to the camera",
+            Action::NextUnit => "Next unit",
+            Action::NextMeasureMode => "Next measure mode",
+            Action::RemoveMeasurement => "Remove the last measurement",
+            Action::ExportMeasurements => "Export the measurements",
+            Action::ExportImage => "Export the image",
+            Action::Screenshot => "Take a screenshot",
+            Action::Quit => "Quit",
+        };
+        description.to_string()
+    }
+
+    /// Continuous actions move something, as long as the key is held. All others happen once,
+    /// when the key is pressed.
+    pub fn is_continuous(&self) -> bool {
+        matches!(self,
+            Action::RotateLeft | Action::RotateRight | Action::RotateUp | Action::RotateDown |
+            Action::RollUp | Action::RollDown | Action::ScaleUp | Action::ScaleDown |
+            Action::MoveXNeg | Action::MoveXPos | Action::MoveYNeg | Action::MoveYPos |
+            Action::MoveZNeg | Action::MoveZPos |
+            Action::ViewUp | Action::ViewDown | Action::ViewLeft | Action::ViewRight |
+            Action::ViewForward | Action::ViewBackward |
+            Action::FlyForward | Action::FlyBackward | Action::FlyLeft | Action::FlyRight |
+            Action::FlyUp | Action::FlyDown)
+    }
+
+    /// The fly actions are only available in the fly mode. There they win over other actions
+    /// bound to the same key, so WASD moves the viewer instead of rotating the object.
+    pub fn is_fly(&self) -> bool {
+        matches!(self,
+            Action::FlyForward | Action::FlyBackward | Action::FlyLeft | Action::FlyRight |
+            Action::FlyUp | Action::FlyDown)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Action::ALL.iter().copied().find(|action| action.name() == name)
+    }
+
+    /// The keys, which are bound to the action, if there is no config file.
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::RotateLeft => &["A"],
+            Action::RotateRight => &["D"],
+            Action::RotateUp => &["W"],
+            Action::RotateDown => &["S"],
+            Action::RollUp => &["Q"],
+            Action::RollDown => &["E"],
+            // Equals is needed because of my Mac-Windows-KB-Mappings
+            Action::ScaleUp => &["Plus", "Equals"],
+            Action::ScaleDown => &["Minus"],
+            Action::MoveXNeg => &["Left"],
+            Action::MoveXPos => &["Right"],
+            Action::MoveYNeg => &["Down"],
+            Action::MoveYPos => &["Up"],
+            Action::MoveZNeg => &["PageUp"],
+            Action::MoveZPos => &["PageDown"],
+            Action::ViewUp => &["Key8", "Numpad8"],
+            Action::ViewDown => &["Key2", "Numpad2"],
+            Action::ViewLeft => &["Key4", "Numpad4"],
+            Action::ViewRight => &["Key6", "Numpad6"],
+            Action::ViewForward => &["Key9", "Numpad9"],
+            Action::ViewBackward => &["Key3", "Numpad3"],
+            Action::ResetView => &["Key5", "Numpad5"],
+            Action::ToggleNavigation => &["F"],
+            Action::FlyForward => &["W"],
+            Action::FlyBackward => &["S"],
+            Action::FlyLeft => &["A"],
+            Action::FlyRight => &["D"],
+            Action::FlyUp => &["Space"],
+            Action::FlyDown => &["C"],
+            Action::ToggleProjection => &["O"],
+            Action::FrontView => &["Ctrl+Key1", "Ctrl+Numpad1"],
+            Action::BackView => &["Ctrl+Key2", "Ctrl+Numpad2"],
+            Action::LeftView => &["Ctrl+Key3", "Ctrl+Numpad3"],
+            Action::RightView => &["Ctrl+Key4", "Ctrl+Numpad4"],
+            Action::TopView => &["Ctrl+Key5", "Ctrl+Numpad5"],
+            Action::BottomView => &["Ctrl+Key6", "Ctrl+Numpad6"],
+            Action::IsometricView => &["Ctrl+Key7", "Ctrl+Numpad7"],
+            Action::WidenFieldOfView => &["RBracket"],
+            Action::NarrowFieldOfView => &["LBracket"],
+            Action::NearCloser => &["Shift+LBracket"],
+            Action::NearFarther => &["Shift+RBracket"],
+            Action::FarCloser => &["Alt+LBracket"],
+            Action::FarFarther => &["Alt+RBracket"],
+            Action::ToggleAutoClip => &["V"],
+            Action::RecallBookmark(number) => &RECALL_BOOKMARK_KEYS[number - 1],
+            Action::StoreBookmark(number) => &STORE_BOOKMARK_KEYS[number - 1],
+            Action::SaveSession => &["Ctrl+S"],
+            Action::Undo => &["Ctrl+Z"],
+            Action::Redo => &["Ctrl+Y", "Ctrl+Shift+Z"],
+            Action::ResetObject => &["Key0", "Numpad0"],
+            Action::ToggleGrid => &["G"],
+            Action::ToggleAxes => &["X"],
+            Action::ToggleGizmo => &["K"],
+            Action::ToggleBounds => &["B"],
+            Action::ToggleOrientedBounds => &["N"],
+            Action::ToggleShadows => &["H"],
+            Action::ToggleSoftShadows => &["Shift+H"],
+            Action::ToggleGround => &["Shift+G"],
+            // Slash is the question mark together with Shift on many keyboards.
+            Action::ToggleHelp => &["F1", "Slash"],
+            Action::ToggleObjectSection => &["F2"],
+            Action::ToggleCameraSection => &["F3"],
+            Action::ToggleDirectionSection => &["F4"],
+            Action::ToggleStatisticsSection => &["F6"],
+            Action::TogglePerformanceSection => &["F7"],
+            Action::ToggleContinuousRendering => &["F8"],
+            Action::ToggleConsole => &["Grave", "F9"],
+            Action::NextLightPreset => &["L"],
+            Action::ToggleCameraLight => &["Shift+L"],
+            Action::NextUnit => &["U"],
+            Action::NextMeasureMode => &["M"],
+            Action::RemoveMeasurement => &["Back"],
+            Action::ExportMeasurements => &["F5"],
+            Action::ExportImage => &["F11"],
+            Action::Screenshot => &["F12"],
+            Action::Quit => &["Escape"],
+        }
+    }
+}
+
+/// A key together with the modifiers, which must be held. In the config file it is written like
+/// "Ctrl+Shift+Z".
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: VirtualKeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+impl KeyChord {
+    /// The key with the currently held modifiers.
+    pub fn new(key: VirtualKeyCode, modifiers: ModifiersState) -> Self {
+        Self { key, ctrl: modifiers.ctrl(), shift: modifiers.shift(), alt: modifiers.alt() }
+    }
+
+    /// The key without any modifiers.
+    pub fn plain(key: VirtualKeyCode) -> Self {
+        Self { key, ctrl: false, shift: false, alt: false }
+    }
+
+    /// Parses a key chord like "Ctrl+Z" or "Numpad5".
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        // A single "+" or a trailing "++" means the plus key itself.
+        if text.ends_with('+') {
+            parts.retain(|part| !part.is_empty());
+            parts.push("Plus");
+        }
+        let key = key_from_name(parts.pop()?)?;
+        let mut chord = KeyChord::plain(key);
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" => chord.alt = true,
+                _ => return None,
+            }
+        }
+        Some(chord)
+    }
+}
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        write!(f, "{:?}", self.key)
+    }
+}
+
+/// The content of the config file. Each entry binds an action to a list of keys, replacing the
+/// default keys of the action.
+#[derive(Deserialize, Default)]
+struct BindingsFile {
+    #[serde(default)]
+    bindings: HashMap<String, Vec<String>>,
+}
+
+/// The key bindings of the application. They are defined as table of actions with their keys,
+/// which also serves as documentation of the controls.
+pub struct Bindings {
+    table: Vec<(Action, Vec<KeyChord>)>,
+    keys: HashMap<KeyChord, Vec<Action>>,
+}
+impl Bindings {
+    /// The bindings of the config file in the config directory of the user. Actions, which are not
+    /// mentioned in the file, keep their default keys. Without a config file, these are the
+    /// default bindings.
+    pub fn load() -> Self {
+        let path = match config_path() {
+            Some(path) if path.exists() => path,
+            _ => return Self::from_file(BindingsFile::default()),
+        };
+        let file = std::fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| toml::from_str(&text).map_err(|error| error.to_string()));
+        match file {
+            Ok(file) => {
+                log::info!("Key bindings loaded from {}", path.display());
+                Self::from_file(file)
+            }
+            Err(error) => {
+                log::error!("Cannot read the key bindings from {}: {error}", path.display());
+                Self::from_file(BindingsFile::default())
+            }
+        }
+    }
+
+    /// Creates the bindings from the config file. Unknown actions and keys are reported and
+    /// ignored.
+    fn from_file(file: BindingsFile) -> Self {
+        let mut table: Vec<(Action, Vec<KeyChord>)> = Action::ALL.iter()
+            .map(|action| (*action, action.default_keys().iter().filter_map(|key| KeyChord::parse(key)).collect()))
+            .collect();
+
+        for (name, keys) in &file.bindings {
+            let action = if let Some(action) = Action::from_name(name) {
+                action
+            } else {
+                log::warn!("[Bindings::from_file()] Unknown action '{name}' in the key bindings");
+                continue;
+            };
+            let chords = keys.iter()
+                .filter_map(|key| {
+                    let chord = KeyChord::parse(key);
+                    if chord.is_none() {
+                        log::warn!("[Bindings::from_file()] Unknown key '{key}' for the action '{name}'");
+                    }
+                    chord
+                })
+                .collect();
+            if let Some(entry) = table.iter_mut().find(|(a, _)| *a == action) {
+                entry.1 = chords;
+            }
+        }
+
+        let mut keys: HashMap<KeyChord, Vec<Action>> = HashMap::new();
+        for (action, chords) in &table {
+            for chord in chords {
+                let actions = keys.entry(*chord).or_default();
+                if let Some(other) = actions.iter().find(|other| other.is_fly() == action.is_fly()) {
+                    log::warn!("[Bindings::from_file()] The key {chord} is bound to {} and {}",
+                               other.name(), action.name());
+                }
+                actions.push(*action);
+            }
+        }
+        let bindings = Self { table, keys };
+        for (action, chords) in bindings.table() {
+            let chords: Vec<String> = chords.iter().map(KeyChord::to_string).collect();
+            log::debug!("Key binding {}: {}", action.name(), chords.join(", "));
+        }
+        bindings
+    }
+
+    /// Finds the action for a key. A binding with exactly the held modifiers wins, otherwise the
+    /// modifiers are ignored, so e.g. Shift may be used to speed up a movement. In the fly mode,
+    /// the fly actions win over the other actions of the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * 'chord' - The key together with the held modifiers.
+    /// * 'navigation' - The current navigation mode.
+    pub fn action(&self, chord: &KeyChord, navigation: Navigation) -> Option<Action> {
+        let find = |chord: &KeyChord| {
+            let actions = self.keys.get(chord)?;
+            let fly = actions.iter().find(|action| action.is_fly());
+            let other = actions.iter().find(|action| !action.is_fly());
+            match navigation {
+                Navigation::Fly => fly.or(other).copied(),
+                Navigation::Orbit => other.copied(),
+            }
+        };
+        find(chord).or_else(|| find(&KeyChord::plain(chord.key)))
+    }
+
+    /// The actions together with their keys, in the order, in which they are presented.
+    pub fn table(&self) -> &[(Action, Vec<KeyChord>)] {
+        &self.table
+    }
+
+    /// The keys and the descriptions of the actions, which may be triggered in the provided
+    /// navigation mode. A key is left out, if it triggers another action in this mode, so the
+    /// help shows exactly, what the keys do.
+    pub fn help(&self, navigation: Navigation) -> Vec<(String, String)> {
+        self.table.iter()
+            .filter_map(|(action, chords)| {
+                let active: Vec<String> = chords.iter()
+                    .filter(|chord| self.action(chord, navigation) == Some(*action))
+                    .map(KeyChord::to_string)
+                    .collect();
+                if active.is_empty() { None } else { Some((active.join(", "), action.description())) }
+            })
+            .collect()
+    }
+}
+
+/// The location of the config file with the key bindings.
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("glium-obj").join("bindings.toml"))
+}
+
+/// Defines the lookup of keys by the names of their VirtualKeyCode.
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        /// Finds the key with the provided name, which is the name of the VirtualKeyCode.
+        fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+            match name {
+                $(stringify!($key) => Some(VirtualKeyCode::$key),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+key_names!(
+    Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+    Snapshot, Scroll, Pause, Insert, Home, Delete, End, PageDown, PageUp,
+    Left, Up, Right, Down, Back, Return, Space, Compose, Caret, Numlock,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    NumpadAdd, NumpadDivide, NumpadDecimal, NumpadComma, NumpadEnter, NumpadEquals,
+    NumpadMultiply, NumpadSubtract,
+    AbntC1, AbntC2, Apostrophe, Apps, Asterisk, At, Ax, Backslash, Calculator, Capital, Colon,
+    Comma, Convert, Equals, Grave, Kana, Kanji, LAlt, LBracket, LControl, LShift, LWin, Mail,
+    MediaSelect, MediaStop, Minus, Mute, MyComputer, NavigateForward, NavigateBackward,
+    NextTrack, NoConvert, OEM102, Period, PlayPause, Plus, Power, PrevTrack, RAlt, RBracket,
+    RControl, RShift, RWin, Semicolon, Slash, Sleep, Stop, Sysrq, Tab, Underline, Unlabeled,
+    VolumeDown, VolumeUp, Wake, WebBack, WebFavorites, WebForward, WebHome, WebRefresh,
+    WebSearch, WebStop, Yen, Copy, Paste, Cut,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(text: &str) -> KeyChord {
+        KeyChord::parse(text).unwrap()
+    }
+
+    #[test]
+    fn parses_keys_with_modifiers() {
+        assert_eq!(chord("Z"), KeyChord::plain(VirtualKeyCode::Z));
+        assert_eq!(chord("Ctrl+Shift+Z"), KeyChord { key: VirtualKeyCode::Z, ctrl: true, shift: true, alt: false });
+        assert_eq!(chord("control + alt + F5"), KeyChord { key: VirtualKeyCode::F5, ctrl: true, shift: false, alt: true });
+        assert_eq!(chord("+"), KeyChord::plain(VirtualKeyCode::Plus));
+        assert_eq!(chord("Ctrl++"), KeyChord { key: VirtualKeyCode::Plus, ctrl: true, shift: false, alt: false });
+        assert_eq!(chord("Ctrl+Shift+Z").to_string(), "Ctrl+Shift+Z");
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_modifiers() {
+        for text in ["", "Nope", "Ctrl+Nope", "Hyper+Z", "Ctrl+Shift", "z"] {
+            assert_eq!(KeyChord::parse(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn prefers_the_exact_chord_over_the_plain_key() {
+        let bindings = Bindings::from_file(BindingsFile::default());
+        let action = |text: &str| bindings.action(&chord(text), Navigation::Orbit);
+        assert_eq!(action("H"), Some(Action::ToggleShadows));
+        assert_eq!(action("Shift+H"), Some(Action::ToggleSoftShadows));
+        // Without a binding of its own, the chord falls back to the plain key.
+        assert_eq!(action("Shift+A"), Some(Action::RotateLeft));
+        assert_eq!(action("Ctrl+Z"), Some(Action::Undo));
+        assert_eq!(action("Z"), None);
+    }
+
+    #[test]
+    fn triggers_fly_actions_only_in_the_fly_mode() {
+        let bindings = Bindings::from_file(BindingsFile::default());
+        let action = |text: &str, navigation| bindings.action(&chord(text), navigation);
+        assert_eq!(action("W", Navigation::Orbit), Some(Action::RotateUp));
+        assert_eq!(action("W", Navigation::Fly), Some(Action::FlyForward));
+        assert_eq!(action("Space", Navigation::Orbit), None);
+        assert_eq!(action("Space", Navigation::Fly), Some(Action::FlyUp));
+        assert_eq!(action("G", Navigation::Fly), Some(Action::ToggleGrid));
+    }
+
+    #[test]
+    fn replaces_the_default_keys_with_the_keys_of_the_file() {
+        let file = BindingsFile {
+            bindings: HashMap::from([(String::from("toggle_grid"), vec![String::from("Ctrl+G"), String::from("Nope")])]),
+        };
+        let bindings = Bindings::from_file(file);
+        assert_eq!(bindings.action(&chord("Ctrl+G"), Navigation::Orbit), Some(Action::ToggleGrid));
+        assert_eq!(bindings.action(&chord("G"), Navigation::Orbit), None);
+        assert_eq!(bindings.action(&chord("Shift+G"), Navigation::Orbit), Some(Action::ToggleGround));
+    }
+}
@@ -0,0 +1,108 @@
+use nalgebra::{Matrix3, SymmetricEigen, Vector3};
+
+use crate::model::Vertex;
+
+/// The axis-aligned bounding box of the object in object coordinates.
+#[derive(Copy, Clone, Debug)]
+pub struct BoundingBox {
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+}
+impl BoundingBox {
+    /// Calculates the bounding box of the provided vertices. An empty list of vertices results in
+    /// an empty box at the origin.
+    pub fn from_vertices(vertices: &[Vertex]) -> Self {
+        if vertices.is_empty() {
+            return Self { min: Vector3::zeros(), max: Vector3::zeros() };
+        }
+        let mut min = Vector3::repeat(f32::MAX);
+        let mut max = Vector3::repeat(f32::MIN);
+        for v in vertices {
+            let p = Vector3::from(v.position);
+            min = min.inf(&p);
+            max = max.sup(&p);
+        }
+        Self { min, max }
+    }
+
+    /// The width (x), height (y) and depth (z) of the box.
+    pub fn size(&self) -> Vector3<f32> {
+        self.max - self.min
+    }
+
+    /// The eight corners of the box. The index of a corner encodes, which coordinate is taken from
+    /// the maximum: Bit 0 for x, bit 1 for y and bit 2 for z.
+    pub fn corners(&self) -> [Vector3<f32>; 8] {
+        let mut corners = [Vector3::zeros(); 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            *corner = Vector3::new(
+                if i & 1 == 0 { self.min[0] } else { self.max[0] },
+                if i & 2 == 0 { self.min[1] } else { self.max[1] },
+                if i & 4 == 0 { self.min[2] } else { self.max[2] },
+            );
+        }
+        corners
+    }
+}
+
+/// A bounding box, which is aligned to the principal axes of the vertex cloud. For parts, which
+/// are modelled at an angle, this is much tighter than the axis-aligned box.
+#[derive(Copy, Clone, Debug)]
+pub struct OrientedBox {
+    pub center: Vector3<f32>,
+    pub axes: Matrix3<f32>,
+    pub half_size: Vector3<f32>,
+}
+impl OrientedBox {
+    /// Calculates the oriented box by a principal component analysis of the vertices. The
+    /// eigenvectors of the covariance matrix provide the axes of the box.
+    pub fn from_vertices(vertices: &[Vertex]) -> Self {
+        if vertices.is_empty() {
+            return Self { center: Vector3::zeros(), axes: Matrix3::identity(), half_size: Vector3::zeros() };
+        }
+        let n = vertices.len() as f32;
+        let mean = vertices.iter()
+            .fold(Vector3::zeros(), |acc, v| acc + Vector3::from(v.position)) / n;
+        let covariance = vertices.iter()
+            .map(|v| Vector3::from(v.position) - mean)
+            .fold(Matrix3::zeros(), |acc, d| acc + d * d.transpose()) / n;
+        let axes = SymmetricEigen::new(covariance).eigenvectors;
+
+        let mut min = Vector3::repeat(f32::MAX);
+        let mut max = Vector3::repeat(f32::MIN);
+        for v in vertices {
+            let p = axes.transpose() * (Vector3::from(v.position) - mean);
+            min = min.inf(&p);
+            max = max.sup(&p);
+        }
+        let center = mean + axes * ((min + max) / 2.0);
+        let half_size = (max - min) / 2.0;
+        Self { center, axes, half_size }
+    }
+
+    /// The edge lengths of the box along its three axes.
+    pub fn size(&self) -> Vector3<f32> {
+        self.half_size * 2.0
+    }
+
+    /// The eight corners of the box, in the same order as for the axis-aligned box.
+    pub fn corners(&self) -> [Vector3<f32>; 8] {
+        let mut corners = [Vector3::zeros(); 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            let local = Vector3::new(
+                if i & 1 == 0 { -self.half_size[0] } else { self.half_size[0] },
+                if i & 2 == 0 { -self.half_size[1] } else { self.half_size[1] },
+                if i & 4 == 0 { -self.half_size[2] } else { self.half_size[2] },
+            );
+            *corner = self.center + self.axes * local;
+        }
+        corners
+    }
+}
+
+/// The twelve edges of a box as pairs of corner indices.
+pub const BOX_EDGES: [(usize, usize); 12] = [
+    (0, 1), (2, 3), (4, 5), (6, 7),
+    (0, 2), (1, 3), (4, 6), (5, 7),
+    (0, 4), (1, 5), (2, 6), (3, 7),
+];
@@ -0,0 +1,183 @@
+use nalgebra::{Unit, UnitQuaternion, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::view::FIELD_OF_VIEW;
+
+// The time in seconds, which the camera needs to move into a standard view.
+const TRANSITION_TIME: f32 = 0.4;
+
+// The smallest and the largest field of view in RAD.
+const MIN_FIELD_OF_VIEW: f32 = 5.0 * std::f32::consts::PI / 180.0;
+const MAX_FIELD_OF_VIEW: f32 = 150.0 * std::f32::consts::PI / 180.0;
+
+// The near plane of the automatic clip range is never closer than this part of the far plane.
+// This limits the loss of depth precision, when the viewer is inside the scene.
+const MIN_NEAR_RATIO: f32 = 1.0e-4;
+
+// The space left around the scene by the automatic clip range.
+const CLIP_MARGIN: f32 = 1.05;
+
+/// The lens of the camera: The field of view and the range between the near and the far plane,
+/// which is visible.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lens {
+    /// The vertical field of view of the perspective in RAD.
+    pub field_of_view: f32,
+    pub near: f32,
+    pub far: f32,
+    /// Fits near and far plane in each frame to the scene, instead of using the fixed planes.
+    pub auto_clip: bool,
+}
+impl Default for Lens {
+    fn default() -> Self {
+        Self { field_of_view: FIELD_OF_VIEW, near: 0.1, far: 1024.0, auto_clip: true }
+    }
+}
+impl Lens {
+    /// Changes the field of view within the sensible limits.
+    pub fn set_field_of_view(&mut self, field_of_view: f32) {
+        self.field_of_view = field_of_view.clamp(MIN_FIELD_OF_VIEW, MAX_FIELD_OF_VIEW);
+    }
+
+    /// The near and the far plane, which are used to render the scene.
+    ///
+    /// # Arguments
+    ///
+    /// * 'depth' - The distance of the center of the scene from the viewer along the line of sight.
+    /// * 'radius' - The radius of the sphere, which contains the scene.
+    /// * 'projection' - The projection decides, whether the near plane may lie behind the viewer.
+    pub fn clip_range(&self, depth: f32, radius: f32, projection: Projection) -> (f32, f32) {
+        match (self.auto_clip, projection) {
+            (false, Projection::Perspective) => (self.near, self.far),
+            // Without a perspective, the position of the viewer does not matter, so nothing in
+            // front of the far plane is clipped.
+            (false, Projection::Orthographic) => (-self.far, self.far),
+            (true, Projection::Perspective) => {
+                let far = (depth + radius * CLIP_MARGIN).max(f32::EPSILON.sqrt());
+                ((depth - radius * CLIP_MARGIN).max(far * MIN_NEAR_RATIO), far)
+            },
+            (true, Projection::Orthographic) => {
+                let radius = radius.max(f32::EPSILON.sqrt()) * CLIP_MARGIN;
+                (depth - radius, depth + radius)
+            },
+        }
+    }
+}
+
+/// The projection of the scene onto the screen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Projection {
+    Perspective,
+    /// The orthographic projection keeps parallel lines parallel. It shows the same extent at the
+    /// distance of the object, as the perspective does.
+    Orthographic,
+}
+impl Projection {
+    /// The next projection in the cycle perspective -> orthographic -> perspective.
+    pub fn next(&self) -> Self {
+        match self {
+            Projection::Perspective => Projection::Orthographic,
+            Projection::Orthographic => Projection::Perspective,
+        }
+    }
+}
+
+/// The standard views of engineering drawings. The viewer looks at the object from the named side.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StandardView {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Isometric,
+}
+impl StandardView {
+    /// The direction from the object to the viewer and the up direction of the screen. The front
+    /// of the object faces the negative z axis, as the obj files are turned into the left handed
+    /// world.
+    pub fn orientation(&self) -> (Vector3<f32>, Vector3<f32>) {
+        let y = Vector3::y();
+        let z = Vector3::z();
+        match self {
+            StandardView::Front => (-z, y),
+            StandardView::Back => (z, y),
+            StandardView::Left => (-Vector3::x(), y),
+            StandardView::Right => (Vector3::x(), y),
+            // Looking straight down or up, the back of the object is at the top of the screen.
+            StandardView::Top => (y, z),
+            StandardView::Bottom => (-y, z),
+            StandardView::Isometric => (Vector3::new(1.0, 1.0, -1.0).normalize(), y),
+        }
+    }
+}
+
+/// A smooth movement of the camera around the target, e.g. into a standard view. The orientation
+/// of the camera and the direction from the target to the camera are interpolated as rotations,
+/// both starting exactly at the current camera. So a camera, which doesn't look at the target,
+/// e.g. after looking around, turns towards it on the way instead of jumping.
+#[derive(Copy, Clone, Debug)]
+pub struct Transition {
+    target: Vector3<f32>,
+    /// The orientation of the camera at the start and at the end of the movement.
+    from: UnitQuaternion<f32>,
+    to: UnitQuaternion<f32>,
+    /// The direction from the target to the camera at the start and at the end of the movement.
+    from_offset: Vector3<f32>,
+    to_offset: Vector3<f32>,
+    /// The distance of the camera from the target, which is kept.
+    distance: f32,
+    elapsed: f32,
+}
+impl Transition {
+    /// Creates the movement from the current camera to the provided view of the target.
+    ///
+    /// # Arguments
+    ///
+    /// * 'target' - The point, the camera looks at, at the end of the movement.
+    /// * 'position', 'direction', 'up' - The current camera.
+    /// * 'offset' - The direction from the target to the camera at the end of the movement.
+    /// * 'up_to' - The up direction of the screen at the end of the movement.
+    pub fn new(target: Vector3<f32>, position: &Vector3<f32>, direction: &Vector3<f32>, up: &Vector3<f32>,
+               offset: &Vector3<f32>, up_to: &Vector3<f32>) -> Self {
+        let distance = (position - target).norm();
+        // A camera in the target keeps its position, whatever the direction.
+        let from_offset = (position - target).try_normalize(f32::EPSILON).unwrap_or(-direction.normalize());
+        let to_offset = offset.try_normalize(f32::EPSILON).unwrap_or(from_offset);
+        Self { target,
+               from: UnitQuaternion::face_towards(direction, up),
+               to: UnitQuaternion::face_towards(&-to_offset, up_to),
+               from_offset, to_offset, distance, elapsed: 0.0 }
+    }
+
+    /// Advances the movement and returns the camera as position, direction and up vector.
+    ///
+    /// # Arguments
+    ///
+    /// * 'seconds' - The time since the last frame.
+    pub fn advance(&mut self, seconds: f32) -> ([f32; 3], [f32; 3], [f32; 3]) {
+        self.elapsed += seconds;
+        let t = (self.elapsed / TRANSITION_TIME).clamp(0.0, 1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+        let rotation = self.from.try_slerp(&self.to, t, f32::EPSILON).unwrap_or(self.to);
+        let direction = rotation * Vector3::z();
+        let up = rotation * Vector3::y();
+        // Opposite directions have no unique rotation between them, so the camera circles around
+        // an axis, which is perpendicular to them.
+        let orbit = UnitQuaternion::scaled_rotation_between(&self.from_offset, &self.to_offset, t)
+            .unwrap_or_else(|| {
+                let axis = self.from_offset.cross(&Vector3::y()).try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vector3::x);
+                UnitQuaternion::from_axis_angle(&Unit::new_unchecked(axis), std::f32::consts::PI * t)
+            });
+        let position = self.target + orbit * self.from_offset * self.distance;
+        (position.into(), direction.into(), up.into())
+    }
+
+    /// True, when the camera has reached the end of the movement.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= TRANSITION_TIME
+    }
+}
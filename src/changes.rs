@@ -0,0 +1,70 @@
+/// The kinds of changes of the model, which the views may be interested in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// The position, direction or lens of the viewer.
+    Camera,
+    /// The rotation, scaling or position of the object.
+    Transform,
+    /// The object itself, i.e. its vertices and triangles.
+    Geometry,
+    /// Everything else, that is shown: Reference geometry, dimensions, picks, measurements and
+    /// the size of the window.
+    Display,
+}
+impl Change {
+    pub const ALL: [Change; 4] = [Change::Camera, Change::Transform, Change::Geometry, Change::Display];
+}
+
+/// Counts the changes of the model. Each change increments the generation, and each kind of change
+/// remembers the generation of its last change. So any number of observers may find out
+/// independently, what has changed since they have looked the last time.
+#[derive(Clone, Debug)]
+pub struct Changes {
+    generation: u64,
+    last: [u64; 4],
+}
+impl Default for Changes {
+    /// A new model counts as changed in all respects, so every observer sees it once.
+    fn default() -> Self {
+        Self { generation: 1, last: [1; 4] }
+    }
+}
+impl Changes {
+    /// Records a change of the provided kind.
+    pub fn notify(&mut self, change: Change) {
+        self.generation += 1;
+        self.last[change as usize] = self.generation;
+    }
+
+    /// The generation of the last change of any kind.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// True, if the provided kind of change happened after the provided generation.
+    pub fn changed_since(&self, generation: u64, change: Change) -> bool {
+        self.last[change as usize] > generation
+    }
+}
+
+/// The interest of an observer in some kinds of changes. It remembers the generation, which the
+/// observer has already seen.
+#[derive(Clone, Debug)]
+pub struct Subscription {
+    kinds: Vec<Change>,
+    seen: u64,
+}
+impl Subscription {
+    /// Subscribes to the provided kinds of changes. The observer has seen nothing so far.
+    pub fn new(kinds: &[Change]) -> Self {
+        Self { kinds: kinds.to_vec(), seen: 0 }
+    }
+
+    /// True, if one of the subscribed kinds has changed since the last call. The changes count as
+    /// seen afterwards.
+    pub fn take(&mut self, changes: &Changes) -> bool {
+        let changed = self.kinds.iter().any(|kind| changes.changed_since(self.seen, *kind));
+        self.seen = changes.generation();
+        changed
+    }
+}
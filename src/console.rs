@@ -0,0 +1,460 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use crate::bindings::Action;
+use crate::camera::{Projection, StandardView};
+use crate::format::{ScaleStyle, MAX_DECIMALS};
+use crate::lighting::{LightPreset, MAX_LIGHTS};
+use crate::navigation::Navigation;
+use crate::units::Unit;
+
+// The number of output lines, which the console keeps.
+const MAX_OUTPUT: usize = 100;
+
+// The number of commands, which the console remembers.
+const MAX_HISTORY: usize = 100;
+
+// The words, which start a command. Besides these, the names of the key binding actions are
+// commands, e.g. "toggle_grid".
+const COMMANDS: [&str; 9] = ["rotate", "scale", "move", "load", "set", "view", "camera", "light", "help"];
+
+// The settings, which may be changed with "set".
+const SETTINGS: [&str; 7] = ["light", "fov", "unit", "projection", "navigation", "precision", "scale_format"];
+
+const VIEWS: [&str; 7] = ["front", "back", "left", "right", "top", "bottom", "isometric"];
+
+const AXES: [&str; 3] = ["x", "y", "z"];
+
+// The words, which may follow "light", besides the numbers of the lights.
+const LIGHT_COMMANDS: [&str; 6] = ["preset", "camera", "ambient", "add", "remove", "save"];
+
+// The properties of a single light.
+const LIGHT_PROPERTIES: [&str; 4] = ["direction", "position", "color", "intensity"];
+
+const LIGHT_USAGE: &str = "Usage: light [preset <name>|camera <on|off>|ambient <value>|add|remove <n>|save|\
+                           <n> direction|position|color <x> <y> <z>|<n> intensity <value>]";
+
+const HELP: &str = "rotate <degrees> <x|y|z>     Rotate the object around an axis
+scale <factor>               Set the scaling factor of the object
+move <x> <y> <z>             Move the object
+load <obj-file>              Load another object
+set light <x> <y> <z>        Set the direction of the key light
+set fov <degrees>            Set the field of view
+set unit <mm|cm|m|in>        Set the unit of the dimensions
+set projection <perspective|orthographic>
+set navigation <orbit|fly>
+set precision <decimals>     Set the decimals of the HUD
+set scale_format <percent|ratio>
+view <front|back|left|right|top|bottom|isometric>
+camera <x> <y> <z> [<x> <y> <z>]
+                             Place the viewer, looking at the object or a target
+light                        List the lights
+light preset <classic|studio|headlight|top>
+light camera <on|off>        Attach the key light (number 1) to the camera
+light ambient <value>        Set the brightness of the ambient light
+light add, light remove <n>  Add a light or remove the light with the number
+light <n> direction <x> <y> <z>
+light <n> position <x> <y> <z>
+light <n> color <r> <g> <b>  Set the color of a light, the channels from 0 to 1
+light <n> intensity <value>
+light save                   Save the lights in the config directory
+<action>                     Any action of the key bindings, e.g. toggle_grid or undo";
+
+/// The commands of the console and of script files.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Rotates the object by an angle in RAD around the x (0), y (1) or z (2) axis.
+    Rotate(f32, usize),
+    Scale(f32),
+    Move([f32; 3]),
+    Load(PathBuf),
+    Set(Setting),
+    View(StandardView),
+    /// Places the viewer at a position, looking at the object or at a target.
+    Camera([f32; 3], Option<[f32; 3]>),
+    Light(LightCommand),
+    /// Performs an action, which is usually triggered by a key.
+    Perform(Action),
+    Help,
+}
+
+/// The settings, which may be changed by the commands.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Setting {
+    Light([f32; 3]),
+    /// The field of view in RAD.
+    FieldOfView(f32),
+    Unit(Unit),
+    Projection(Projection),
+    Navigation(Navigation),
+    Precision(usize),
+    ScaleFormat(ScaleStyle),
+}
+
+/// The changes of the lights. The lights are numbered from 1, the first one is the key light.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LightCommand {
+    List,
+    Preset(LightPreset),
+    /// Attaches the key light to the camera or detaches it.
+    Camera(bool),
+    Ambient(f32),
+    Add,
+    Remove(usize),
+    Save,
+    /// Makes a light a directional light, which falls from the direction.
+    Direction(usize, [f32; 3]),
+    /// Makes a light a point light at the position.
+    Position(usize, [f32; 3]),
+    Color(usize, [f32; 3]),
+    Intensity(usize, f32),
+}
+
+impl Command {
+    /// Parses a line of the console or of a script. Words are separated by white space.
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (&command, arguments) = words.split_first().ok_or("Empty command")?;
+        match command {
+            "rotate" => match arguments {
+                [angle, axis] => Ok(Command::Rotate(number(angle)?.to_radians(), axis_index(axis)?)),
+                _ => Err(String::from("Usage: rotate <degrees> <x|y|z>")),
+            },
+            "scale" => match arguments {
+                [factor] => match number(factor)? {
+                    factor if factor > 0.0 => Ok(Command::Scale(factor)),
+                    _ => Err(format!("The scaling factor must be positive, not '{factor}'")),
+                },
+                _ => Err(String::from("Usage: scale <factor>")),
+            },
+            "move" => Ok(Command::Move(vector(arguments).ok_or("Usage: move <x> <y> <z>")??)),
+            "load" => match arguments {
+                [] => Err(String::from("Usage: load <obj-file>")),
+                // File names may contain spaces.
+                _ => Ok(Command::Load(PathBuf::from(arguments.join(" ")))),
+            },
+            "set" => Ok(Command::Set(Setting::parse(arguments)?)),
+            "view" => match arguments {
+                [name] => Ok(Command::View(standard_view(name)?)),
+                _ => Err(format!("Usage: view <{}>", VIEWS.join("|"))),
+            },
+            "camera" => match arguments.split_at(arguments.len().min(3)) {
+                (position, []) if position.len() == 3 => Ok(Command::Camera(vector(position).unwrap()?, None)),
+                (position, target) => match (vector(position), vector(target)) {
+                    (Some(position), Some(target)) => Ok(Command::Camera(position?, Some(target?))),
+                    _ => Err(String::from("Usage: camera <x> <y> <z> [<target-x> <target-y> <target-z>]")),
+                },
+            },
+            "light" => Ok(Command::Light(LightCommand::parse(arguments)?)),
+            "help" => Ok(Command::Help),
+            _ => match (Action::from_name(command), arguments) {
+                (Some(action), []) if is_command(action) => Ok(Command::Perform(action)),
+                (Some(_), []) => Err(format!("The action '{command}' is only available as key")),
+                (Some(_), _) => Err(format!("The action '{command}' takes no arguments")),
+                (None, _) => Err(format!("Unknown command '{command}', try help")),
+            },
+        }
+    }
+}
+
+impl Setting {
+    /// Parses the name and the values of a setting.
+    fn parse(arguments: &[&str]) -> Result<Self, String> {
+        match arguments {
+            ["light", values @ ..] => match vector(values).ok_or("Usage: set light <x> <y> <z>")?? {
+                [0.0, 0.0, 0.0] => Err(String::from("The light needs a direction, not (0, 0, 0)")),
+                direction => Ok(Setting::Light(direction)),
+            },
+            ["fov", degrees] => Ok(Setting::FieldOfView(number(degrees)?.to_radians())),
+            ["unit", unit] => Ok(Setting::Unit(unit.parse()?)),
+            ["projection", "perspective"] => Ok(Setting::Projection(Projection::Perspective)),
+            ["projection", "orthographic"] => Ok(Setting::Projection(Projection::Orthographic)),
+            ["navigation", "orbit"] => Ok(Setting::Navigation(Navigation::Orbit)),
+            ["navigation", "fly"] => Ok(Setting::Navigation(Navigation::Fly)),
+            ["precision", decimals] => match decimals.parse() {
+                Ok(precision) if precision <= MAX_DECIMALS => Ok(Setting::Precision(precision)),
+                _ => Err(format!("The precision needs a number of decimals from 0 to {MAX_DECIMALS}, not '{decimals}'")),
+            },
+            ["scale_format", style] => Ok(Setting::ScaleFormat(style.parse()?)),
+            _ => Err(format!("Usage: set <{}> <value>", SETTINGS.join("|"))),
+        }
+    }
+}
+
+impl LightCommand {
+    /// Parses the arguments of the light command.
+    fn parse(arguments: &[&str]) -> Result<Self, String> {
+        let values = |values: &[&str]| vector(values).ok_or(LIGHT_USAGE)?;
+        match arguments {
+            [] => Ok(LightCommand::List),
+            ["preset", name] => Ok(LightCommand::Preset(name.parse()?)),
+            ["camera", "on"] => Ok(LightCommand::Camera(true)),
+            ["camera", "off"] => Ok(LightCommand::Camera(false)),
+            ["ambient", value] => Ok(LightCommand::Ambient(not_negative(value)?)),
+            ["add"] => Ok(LightCommand::Add),
+            ["remove", number] => Ok(LightCommand::Remove(light_number(number)?)),
+            ["save"] => Ok(LightCommand::Save),
+            [number, "direction", direction @ ..] => match values(direction)? {
+                [0.0, 0.0, 0.0] => Err(String::from("The light needs a direction, not (0, 0, 0)")),
+                direction => Ok(LightCommand::Direction(light_number(number)?, direction)),
+            },
+            [number, "position", position @ ..] => Ok(LightCommand::Position(light_number(number)?, values(position)?)),
+            [number, "color", color @ ..] => match values(color)? {
+                color if color.iter().all(|channel| *channel >= 0.0) => Ok(LightCommand::Color(light_number(number)?, color)),
+                _ => Err(String::from("The channels of the color must not be negative")),
+            },
+            [number, "intensity", value] => Ok(LightCommand::Intensity(light_number(number)?, not_negative(value)?)),
+            _ => Err(String::from(LIGHT_USAGE)),
+        }
+    }
+}
+
+/// The lines of the help of the commands.
+pub fn help() -> impl Iterator<Item = &'static str> {
+    HELP.lines()
+}
+
+/// True, if an action may be performed by a command. Continuous actions need a held key, and the
+/// application is only left by its key.
+fn is_command(action: Action) -> bool {
+    !action.is_continuous() && !matches!(action, Action::Quit | Action::ToggleConsole)
+}
+
+fn number(word: &str) -> Result<f32, String> {
+    match word.parse::<f32>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        _ => Err(format!("'{word}' is not a number")),
+    }
+}
+
+/// Three numbers, if there are three words.
+fn vector(words: &[&str]) -> Option<Result<[f32; 3], String>> {
+    match words {
+        [x, y, z] => Some((|| Ok([number(x)?, number(y)?, number(z)?]))()),
+        _ => None,
+    }
+}
+
+fn not_negative(word: &str) -> Result<f32, String> {
+    match number(word)? {
+        value if value >= 0.0 => Ok(value),
+        _ => Err(format!("'{word}' must not be negative")),
+    }
+}
+
+/// The number of a light from 1 to MAX_LIGHTS.
+fn light_number(word: &str) -> Result<usize, String> {
+    match word.parse() {
+        Ok(number) if (1..=MAX_LIGHTS).contains(&number) => Ok(number),
+        _ => Err(format!("'{word}' is no light, expected a number from 1 to {MAX_LIGHTS}")),
+    }
+}
+
+fn axis_index(word: &str) -> Result<usize, String> {
+    AXES.iter().position(|axis| *axis == word).ok_or_else(|| format!("Unknown axis '{word}', expected x, y or z"))
+}
+
+fn standard_view(word: &str) -> Result<StandardView, String> {
+    let views = [StandardView::Front, StandardView::Back, StandardView::Left, StandardView::Right,
+                 StandardView::Top, StandardView::Bottom, StandardView::Isometric];
+    VIEWS.iter().position(|view| *view == word)
+        .map(|i| views[i])
+        .ok_or_else(|| format!("Unknown view '{word}', expected one of {}", VIEWS.join(", ")))
+}
+
+/// The drop-down console, where commands are typed. It keeps the typed commands as history and
+/// the results of the commands as output.
+#[derive(Clone, Debug, Default)]
+pub struct Console {
+    pub open: bool,
+    /// The command, which is currently typed.
+    pub input: String,
+    history: Vec<String>,
+    /// The entry of the history, which is currently shown in the input.
+    history_position: Option<usize>,
+    output: VecDeque<String>,
+}
+impl Console {
+    /// Opens or closes the console.
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    /// The last lines of the output, the latest at the back.
+    pub fn output(&self, lines: usize) -> impl Iterator<Item = &String> {
+        self.output.iter().skip(self.output.len().saturating_sub(lines))
+    }
+
+    /// Adds a line to the output.
+    pub fn print(&mut self, line: &str) {
+        for line in line.lines() {
+            if self.output.len() == MAX_OUTPUT {
+                self.output.pop_front();
+            }
+            self.output.push_back(line.to_string());
+        }
+    }
+
+    /// Adds a typed character to the input.
+    pub fn type_character(&mut self, character: char) {
+        self.input.push(character);
+        self.history_position = None;
+    }
+
+    /// Removes the last character of the input.
+    pub fn backspace(&mut self) {
+        self.input.pop();
+        self.history_position = None;
+    }
+
+    /// Takes the typed command. It is echoed to the output and remembered in the history.
+    pub fn submit(&mut self) -> Option<String> {
+        let line = std::mem::take(&mut self.input);
+        self.history_position = None;
+        if line.trim().is_empty() {
+            return None;
+        }
+        self.print(&format!("> {line}"));
+        if self.history.last() != Some(&line) {
+            if self.history.len() == MAX_HISTORY {
+                self.history.remove(0);
+            }
+            self.history.push(line.clone());
+        }
+        Some(line)
+    }
+
+    /// Shows the previous command of the history in the input.
+    pub fn history_back(&mut self) {
+        let position = match self.history_position {
+            Some(position) => position.saturating_sub(1),
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+        };
+        self.history_position = Some(position);
+        self.input = self.history[position].clone();
+    }
+
+    /// Shows the next command of the history in the input, or an empty input after the last one.
+    pub fn history_forward(&mut self) {
+        match self.history_position {
+            Some(position) if position + 1 < self.history.len() => {
+                self.history_position = Some(position + 1);
+                self.input = self.history[position + 1].clone();
+            },
+            Some(_) => {
+                self.history_position = None;
+                self.input.clear();
+            },
+            None => (),
+        }
+    }
+
+    /// Completes the last word of the input. If several words fit, the input is completed as far
+    /// as they agree and the candidates are printed.
+    pub fn complete(&mut self) {
+        let start = self.input.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let (before, prefix) = self.input.split_at(start);
+        let words: Vec<&str> = before.split_whitespace().collect();
+        let candidates: Vec<String> = candidates(&words, prefix).into_iter()
+            .filter(|candidate| candidate.starts_with(prefix))
+            .collect();
+        match candidates.as_slice() {
+            [] => (),
+            [candidate] => {
+                let separator = if candidate.ends_with('/') { "" } else { " " };
+                self.input = format!("{before}{candidate}{separator}");
+            },
+            _ => {
+                let common = candidates.iter().skip(1).fold(candidates[0].as_str(), |common, candidate| {
+                    let length = common.chars().zip(candidate.chars())
+                        .take_while(|(a, b)| a == b)
+                        .map(|(a, _)| a.len_utf8())
+                        .sum();
+                    &common[..length]
+                });
+                let line = candidates.join("  ");
+                self.input = format!("{before}{common}");
+                self.print(&line);
+            },
+        }
+    }
+}
+
+/// The words, which may follow the provided words of a command.
+///
+/// # Arguments
+///
+/// * 'words' - The complete words of the command.
+/// * 'prefix' - The start of the word, which is completed.
+fn candidates(words: &[&str], prefix: &str) -> Vec<String> {
+    let strings = |words: &[&str]| words.iter().map(|word| word.to_string()).collect();
+    match words {
+        [] => COMMANDS.iter().map(|command| command.to_string())
+            .chain(Action::ALL.iter().filter(|action| is_command(**action)).map(|action| action.name().to_string()))
+            .collect(),
+        ["rotate", _] => strings(&AXES),
+        ["set"] => strings(&SETTINGS),
+        ["set", "unit"] => strings(&["mm", "cm", "m", "in"]),
+        ["set", "projection"] => strings(&["perspective", "orthographic"]),
+        ["set", "navigation"] => strings(&["orbit", "fly"]),
+        ["set", "scale_format"] => strings(&["percent", "ratio"]),
+        ["view"] => strings(&VIEWS),
+        ["light"] => LIGHT_COMMANDS.iter().map(|command| command.to_string())
+            .chain((1..=MAX_LIGHTS).map(|number| number.to_string()))
+            .collect(),
+        ["light", "preset"] => LightPreset::ALL.iter().map(|preset| preset.name().to_string()).collect(),
+        ["light", "camera"] => strings(&["on", "off"]),
+        ["light", number] if light_number(number).is_ok() => strings(&LIGHT_PROPERTIES),
+        ["load"] => files(prefix),
+        _ => Vec::new(),
+    }
+}
+
+/// The obj files and directories in the directory of a path, which is typed. They start with
+/// the directory as typed, so they may replace the path.
+fn files(prefix: &str) -> Vec<String> {
+    let dir = &prefix[..prefix.rfind('/').map_or(0, |i| i + 1)];
+    let entries = match std::fs::read_dir(if dir.is_empty() { Path::new(".") } else { Path::new(dir) }) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut files: Vec<String> = entries.filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().is_dir() {
+                Some(format!("{dir}{name}/"))
+            } else if name.ends_with(".obj") {
+                Some(format!("{dir}{name}"))
+            } else {
+                None
+            }
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_camera_position() {
+        assert_eq!(Command::parse("camera 3 1 -3"), Ok(Command::Camera([3.0, 1.0, -3.0], None)));
+    }
+
+    #[test]
+    fn parses_camera_position_and_target() {
+        assert_eq!(Command::parse("camera 3 1 -3  0 0 2"),
+                   Ok(Command::Camera([3.0, 1.0, -3.0], Some([0.0, 0.0, 2.0]))));
+    }
+
+    #[test]
+    fn rejects_bad_camera_arguments() {
+        for line in ["camera", "camera 1 2", "camera 1 2 3 4", "camera 1 2 3 4 5 6 7", "camera a 2 3",
+                     "camera 1 2 3 0 0 x", "camera 1 2 inf"] {
+            assert!(Command::parse(line).is_err(), "{line}");
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use glium::{Display, Program, Surface, VertexBuffer};
+use glium::index::PrimitiveType;
+use nalgebra::{Matrix4, Point3, Vector3};
+
+use crate::bounds::BOX_EDGES;
+use crate::hud::HudView;
+use crate::model::Model;
+use crate::performance::DrawStats;
+use crate::view::{Viewport, depth_test, project, view_matrix, world_matrix};
+
+const AABB_COLOR: [f32; 3] = [1.0, 1.0, 0.3];
+const OBB_COLOR: [f32; 3] = [1.0, 0.5, 0.1];
+
+/// A colored vertex of the box wireframes.
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+implement_vertex!(Vertex, position, color);
+
+/// Shows the bounding boxes of the object as wireframes. The edges of the axis-aligned box are
+/// labeled with the width, height and depth of the object in the selected unit.
+pub struct DimensionView {
+    program: Program,
+}
+impl DimensionView {
+    pub fn new(display: &Display) -> Self {
+        let vertex_shader_src = include_str!("reference.vertex.glsl");
+        let fragment_shader_src = include_str!("reference.fragment.glsl");
+        let program = Program::from_source(display, vertex_shader_src, fragment_shader_src,
+                                           None).unwrap();
+        Self { program }
+    }
+
+    /// Draws the enabled bounding boxes and queues the dimension labels in the HUD. The boxes
+    /// follow the rotation, scaling and movement of the object.
+    pub fn draw<S: Surface>(&self, display: &Display, target: &mut S, hud: &mut HudView, model: &Model,
+                            viewport: &Viewport, stats: &mut DrawStats) {
+        if !model.show_bounds && !model.show_oriented_bounds {
+            return;
+        }
+
+        let world = world_matrix(model);
+        let mut vertices = Vec::new();
+        if model.show_bounds {
+            push_box(&mut vertices, &world, &model.bounds.corners(), AABB_COLOR);
+        }
+        if model.show_oriented_bounds {
+            push_box(&mut vertices, &world, &model.oriented_bounds.corners(), OBB_COLOR);
+        }
+        let buffer = VertexBuffer::new(display, &vertices).unwrap();
+
+        let view = view_matrix(&model.view_position, &model.view_direction, &model.up);
+        let perspective = viewport.projection(model);
+        let params = glium::DrawParameters {
+            depth: depth_test(),
+            .. Default::default()
+        };
+        target.draw(&buffer,
+                    glium::index::NoIndices(PrimitiveType::LinesList),
+                    &self.program,
+                    &uniform! { view: view, perspective: perspective },
+                    &params).unwrap();
+        stats.count(0);
+
+        if model.show_bounds {
+            let corners = model.bounds.corners();
+            let size = model.bounds.size() * model.scaling_factor;
+            // The edges, which start at the minimum corner, run along x, y and z.
+            for (axis, name) in ["W", "H", "D"].iter().enumerate() {
+                let middle = (corners[0] + corners[1 << axis]) / 2.0;
+                let position = world.transform_point(&Point3::from(middle));
+                if let Some(screen) = project(model, &position.coords, viewport.width, viewport.height) {
+                    hud.queue_label(&format_length(name, size[axis], model), screen);
+                }
+            }
+        }
+        if model.show_oriented_bounds {
+            let size = model.oriented_bounds.size() * model.scaling_factor;
+            let position = world.transform_point(&Point3::from(model.oriented_bounds.center));
+            if let Some(screen) = project(model, &position.coords, viewport.width, viewport.height) {
+                let text = format!("{} x {} x {} {}",
+                                   format_value(size[0], model),
+                                   format_value(size[1], model),
+                                   format_value(size[2], model),
+                                   model.unit);
+                hud.queue_label(&text, screen);
+            }
+        }
+    }
+}
+
+/// Appends the twelve edges of a box to the vertex list.
+///
+/// # Arguments
+///
+/// * 'vertices' - The vertex list of the lines.
+/// * 'world' - The transformation of the object into the world coordinates.
+/// * 'corners' - The corners of the box in object coordinates.
+/// * 'color' - The color of the edges.
+fn push_box(vertices: &mut Vec<Vertex>, world: &Matrix4<f32>, corners: &[Vector3<f32>; 8], color: [f32; 3]) {
+    for (a, b) in BOX_EDGES {
+        for corner in [corners[a], corners[b]] {
+            let p = world.transform_point(&Point3::from(corner));
+            vertices.push(Vertex { position: p.coords.into(), color });
+        }
+    }
+}
+
+/// Formats a length, given in world coordinates, as a labeled value in the selected unit.
+fn format_length(name: &str, length: f32, model: &Model) -> String {
+    format!("{name} {} {}", format_value(length, model), model.unit)
+}
+
+/// Converts a length in world coordinates into the selected unit and formats it with the decimals
+/// of the HUD.
+fn format_value(length: f32, model: &Model) -> String {
+    model.hud_format.number(model.model_unit.convert(length, model.unit))
+}
@@ -0,0 +1,153 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use glium::{glutin, Display};
+use glium::glutin::event_loop::EventLoop;
+
+use crate::measure::Measurement;
+use crate::model::Model;
+use crate::view::{RenderOptions, View};
+
+/// The largest multiple of the window size, which is exported.
+pub const MAX_EXPORT_MULTIPLE: u32 = 16;
+
+// The files with the same time in their name, which are numbered, before the export gives up.
+const MAX_SAME_NAMES: u32 = 1000;
+
+/// The settings of the image export. The image is a multiple of the window size.
+#[derive(Copy, Clone, Debug)]
+pub struct ExportSettings {
+    pub multiple: u32,
+    pub hud: bool,
+    pub transparent: bool,
+}
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self { multiple: 4, hud: true, transparent: false }
+    }
+}
+
+/// The kinds of images, the user may request.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExportRequest {
+    /// The picture exactly as it is shown in the window.
+    Screenshot,
+    /// The picture with the export settings.
+    Image,
+}
+
+/// Renders the requested image and writes it as PNG into the current directory. The name of the
+/// file contains the time of the export and never replaces an existing file.
+///
+/// # Arguments
+///
+/// * 'display' - The display to render with.
+/// * 'view' - The view, which renders the model.
+/// * 'model' - The model to render.
+/// * 'request' - The kind of image to export.
+pub fn export_image(display: &Display, view: &mut View, model: &Model, request: ExportRequest)
+    -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let (prefix, multiple, options) = match request {
+        ExportRequest::Screenshot => ("screenshot", 1, RenderOptions { hud: true, transparent: false }),
+        ExportRequest::Image => ("export", model.export.multiple.max(1),
+                                 RenderOptions { hud: model.export.hud, transparent: model.export.transparent }),
+    };
+    let (width, height, pixels) = view.render_image(display, model, multiple, &options)?;
+    let (file, path) = create_new_file(prefix, "png")?;
+    image::write_buffer_with_format(&mut BufWriter::new(file), &pixels, width, height, image::ColorType::Rgba8,
+                                    image::ImageFormat::Png)?;
+    Ok(path)
+}
+
+/// Creates a display with an invisible window, which is used to render images without showing
+/// anything on the screen. The event loop must live as long as the display.
+pub fn hidden_display() -> Result<(EventLoop<()>, Display), Box<dyn std::error::Error>> {
+    let event_loop = EventLoop::new();
+    let wb = glutin::window::WindowBuilder::new()
+        .with_title("Obj viewer based on obj-rs")
+        .with_visible(false);
+    let cb = glutin::ContextBuilder::new()
+        .with_depth_buffer(24);
+    let display = Display::new(wb, cb, &event_loop)?;
+    Ok((event_loop, display))
+}
+
+/// Writes RGBA pixels, starting at the top left corner, as PNG file.
+pub fn write_png(path: &Path, width: u32, height: u32, pixels: &[u8]) -> image::ImageResult<()> {
+    image::save_buffer(path, pixels, width, height, image::ColorType::Rgba8)
+}
+
+/// Writes all measurements into a CSV file in the current directory. The name of the file contains
+/// the time of the export and never replaces an existing file. Distances are given in the selected unit, angles in degrees. The points
+/// are given in the coordinates of the obj file, i.e. right handed and without the scaling of the
+/// viewer, so they can be found in the original file.
+///
+/// # Arguments
+///
+/// * 'model' - The model with the measurements.
+pub fn write_measurements(model: &Model) -> std::io::Result<PathBuf> {
+    let (file, path) = create_new_file("measurements", "csv")?;
+    let mut out = BufWriter::new(file);
+
+    writeln!(out, "index,kind,value,unit,x1,y1,z1,x2,y2,z2,x3,y3,z3")?;
+    for (i, measurement) in model.measurements.iter().enumerate() {
+        let unit = match measurement {
+            Measurement::Distance(_) => model.unit.symbol(),
+            Measurement::Angle(_) => "deg",
+        };
+        write!(out, "{},{},{},{}", i + 1, measurement.kind(), measurement.value(model), unit)?;
+        for p in measurement.points() {
+            write!(out, ",{},{},{}", p[0], p[1], -p[2])?;
+        }
+        writeln!(out)?;
+    }
+    out.flush()?;
+    Ok(path)
+}
+
+/// Creates a new file in the current directory, whose name contains the current time. If the name
+/// is taken, e.g. by a second export within the same second, a number is appended, like in
+/// screenshot-20240101-120000-2.png. An existing file is never replaced.
+///
+/// # Arguments
+///
+/// * 'prefix' - The start of the name, like "screenshot".
+/// * 'extension' - The extension of the file without the dot.
+fn create_new_file(prefix: &str, extension: &str) -> std::io::Result<(File, PathBuf)> {
+    let stem = format!("{prefix}-{}", timestamp());
+    for number in 1..=MAX_SAME_NAMES {
+        let path = match number {
+            1 => PathBuf::from(format!("{stem}.{extension}")),
+            _ => PathBuf::from(format!("{stem}-{number}.{extension}")),
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    Err(std::io::Error::new(ErrorKind::AlreadyExists, format!("There are too many files named {stem}*.{extension}")))
+}
+
+/// The current time (UTC) in the form YYYYMMDD-hhmmss, used to name exported files.
+pub fn timestamp() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let days = (seconds / 86_400) as i64;
+    let time = seconds % 86_400;
+
+    // Conversion of the days since 1970-01-01 into the civil date (H. Hinnant).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}",
+            year, month, day, time / 3600, time % 3600 / 60, time % 60)
+}
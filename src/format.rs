@@ -0,0 +1,136 @@
+use std::str::FromStr;
+
+use crate::units::Unit;
+
+/// The largest number of decimals of the HUD values. More digits than a f32 has are just noise.
+pub const MAX_DECIMALS: usize = 6;
+
+/// The way, the scaling factor of the object is presented.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScaleStyle {
+    /// The size compared to the loaded object, like "250%".
+    Percent,
+    /// The ratio of the size to the loaded object, like "2.5:1" or "1:4".
+    Ratio,
+}
+impl FromStr for ScaleStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "percent" | "%" => Ok(ScaleStyle::Percent),
+            "ratio" => Ok(ScaleStyle::Ratio),
+            _ => Err(format!("Unknown scale format '{s}', expected percent or ratio")),
+        }
+    }
+}
+
+/// Formats the values of the HUD. All numbers are shown with the same number of decimals, angles
+/// in degrees.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HudFormat {
+    /// The number of decimals of lengths and angles.
+    pub precision: usize,
+    pub scale_style: ScaleStyle,
+}
+impl Default for HudFormat {
+    fn default() -> Self {
+        Self { precision: 2, scale_style: ScaleStyle::Percent }
+    }
+}
+impl HudFormat {
+    /// A number with the configured decimals. Values, which round to zero, are shown without a
+    /// sign, so the HUD doesn't flicker between "-0.00" and "0.00".
+    pub fn number(&self, value: f32) -> String {
+        let text = format!("{:.*}", self.precision, value);
+        match text.strip_prefix('-') {
+            Some(unsigned) if unsigned.chars().all(|c| c == '0' || c == '.') => unsigned.to_string(),
+            _ => text,
+        }
+    }
+
+    /// An angle given in RAD as degrees between -180° and 180°.
+    pub fn angle(&self, radians: f32) -> String {
+        format!("{}°", self.number(wrap_degrees(radians.to_degrees())))
+    }
+
+    /// A position as its coordinates in the unit of the dimensions.
+    ///
+    /// # Arguments
+    ///
+    /// * 'position' - The position in the coordinates of the obj file.
+    /// * 'model_unit' - The unit of the coordinates in the obj file.
+    /// * 'unit' - The unit of the result.
+    pub fn position(&self, position: &[f32; 3], model_unit: Unit, unit: Unit) -> String {
+        let [x, y, z] = position.map(|value| model_unit.convert(value, unit));
+        format!("({}, {}, {}) {unit}", self.number(x), self.number(y), self.number(z))
+    }
+
+    /// A direction as its coordinates. The direction is normalized, so its coordinates may be
+    /// compared.
+    pub fn direction(&self, direction: &[f32; 3]) -> String {
+        let length = direction.iter().map(|value| value * value).sum::<f32>().sqrt();
+        let [x, y, z] = if length > 0.0 { direction.map(|value| value / length) } else { *direction };
+        format!("({}, {}, {})", self.number(x), self.number(y), self.number(z))
+    }
+
+    /// The scaling factor of the object as percentage or ratio.
+    pub fn scale(&self, factor: f32) -> String {
+        match self.scale_style {
+            ScaleStyle::Percent => format!("{}%", self.number(factor * 100.0)),
+            ScaleStyle::Ratio if factor >= 1.0 => format!("{}:1", self.number(factor)),
+            ScaleStyle::Ratio => format!("1:{}", self.number(1.0 / factor)),
+        }
+    }
+}
+
+/// An angle in degrees wrapped into the range from -180° to 180°.
+pub fn wrap_degrees(degrees: f32) -> f32 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(precision: usize, scale_style: ScaleStyle) -> HudFormat {
+        HudFormat { precision, scale_style }
+    }
+
+    #[test]
+    fn wraps_degrees_into_half_turns() {
+        assert_eq!(wrap_degrees(0.0), 0.0);
+        assert_eq!(wrap_degrees(190.0), -170.0);
+        assert_eq!(wrap_degrees(-190.0), 170.0);
+        assert_eq!(wrap_degrees(720.0), 0.0);
+        assert_eq!(wrap_degrees(180.0), -180.0);
+    }
+
+    #[test]
+    fn formats_numbers_with_the_precision() {
+        assert_eq!(format(2, ScaleStyle::Percent).number(1.23456), "1.23");
+        assert_eq!(format(0, ScaleStyle::Percent).number(1.5), "2");
+        assert_eq!(format(4, ScaleStyle::Percent).number(-2.0), "-2.0000");
+        assert_eq!(format(2, ScaleStyle::Percent).number(-0.001), "0.00");
+        assert_eq!(format(0, ScaleStyle::Percent).number(-0.2), "0");
+    }
+
+    #[test]
+    fn formats_angles_in_degrees() {
+        let format = format(1, ScaleStyle::Percent);
+        assert_eq!(format.angle(std::f32::consts::FRAC_PI_2), "90.0°");
+        assert_eq!(format.angle(3.0 * std::f32::consts::FRAC_PI_2), "-90.0°");
+    }
+
+    #[test]
+    fn formats_positions_directions_and_scales() {
+        let format = format(1, ScaleStyle::Percent);
+        assert_eq!(format.position(&[1.0, -2.0, 0.25], Unit::Meter, Unit::Centimeter), "(100.0, -200.0, 25.0) cm");
+        assert_eq!(format.direction(&[0.0, 3.0, -4.0]), "(0.0, 0.6, -0.8)");
+        assert_eq!(format.direction(&[0.0; 3]), "(0.0, 0.0, 0.0)");
+        assert_eq!(format.scale(2.5), "250.0%");
+        let ratio = HudFormat { scale_style: ScaleStyle::Ratio, ..format };
+        assert_eq!(ratio.scale(2.5), "2.5:1");
+        assert_eq!(ratio.scale(0.25), "1:4.0");
+    }
+}
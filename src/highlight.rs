@@ -0,0 +1,76 @@
+use glium::{Display, Program, Surface, VertexBuffer};
+use glium::index::{NoIndices, PrimitiveType};
+use nalgebra::{Point3, Vector3};
+
+use crate::model::Model;
+use crate::performance::DrawStats;
+use crate::picking::Pick;
+use crate::view::{Viewport, surface_depth_test, view_matrix, world_matrix};
+
+const FACE_COLOR: [f32; 3] = [1.0, 0.9, 0.0];
+const EDGE_COLOR: [f32; 3] = [0.0, 0.0, 0.0];
+
+// The highlight is moved by this part of its distance towards the viewer, so it is drawn on top
+// of the surface, it lies on, but stays hidden behind the parts of the object in front of it.
+const DEPTH_BIAS: f32 = 0.002;
+
+/// A colored vertex of the highlighted face.
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+implement_vertex!(Vertex, position, color);
+
+/// Highlights the picked triangle of the object.
+pub struct HighlightView {
+    program: Program,
+}
+impl HighlightView {
+    pub fn new(display: &Display) -> Self {
+        let vertex_shader_src = include_str!("reference.vertex.glsl");
+        let fragment_shader_src = include_str!("reference.fragment.glsl");
+        let program = Program::from_source(display, vertex_shader_src, fragment_shader_src,
+                                           None).unwrap();
+        Self { program }
+    }
+
+    /// Draws the face of the current pick with an outline. The face lies exactly on the surface
+    /// of the object, so it is moved a little towards the viewer and passes the depth test at the
+    /// same depth.
+    pub fn draw<S: Surface>(&self, display: &Display, target: &mut S, model: &Model, viewport: &Viewport,
+                            stats: &mut DrawStats) {
+        let pick = if let Some(pick) = model.current_pick() { pick } else { return };
+
+        let view = view_matrix(&model.view_position, &model.view_direction, &model.up);
+        let perspective = viewport.projection(model);
+        let uniforms = uniform! { view: view, perspective: perspective };
+        let params = glium::DrawParameters {
+            depth: surface_depth_test(),
+            line_width: Some(2.0),
+            .. Default::default()
+        };
+
+        let face = VertexBuffer::new(display, &face_vertices(model, pick, FACE_COLOR)).unwrap();
+        target.draw(&face, NoIndices(PrimitiveType::TrianglesList), &self.program, &uniforms, &params).unwrap();
+        stats.count(1);
+        let edges = VertexBuffer::new(display, &face_vertices(model, pick, EDGE_COLOR)).unwrap();
+        target.draw(&edges, NoIndices(PrimitiveType::LineLoop), &self.program, &uniforms, &params).unwrap();
+        stats.count(0);
+    }
+}
+
+/// The corners of the picked triangle in world coordinates, moved a little towards the viewer.
+fn face_vertices(model: &Model, pick: &Pick, color: [f32; 3]) -> Vec<Vertex> {
+    let world = world_matrix(model);
+    let eye = Vector3::from(model.view_position);
+    let line_of_sight = model.line_of_sight();
+    pick.vertices.iter()
+        .map(|&i| {
+            let p = world.transform_point(&Point3::from(model.object.vertices[i].position)).coords;
+            let distance = (p - eye).dot(&line_of_sight).abs();
+            let p = p - line_of_sight * (DEPTH_BIAS * distance);
+            Vertex { position: p.into(), color }
+        })
+        .collect()
+}
@@ -0,0 +1,145 @@
+use std::time::{Duration, Instant};
+
+use crate::session::Bookmark;
+
+// The number of steps, which may be undone.
+const MAX_STEPS: usize = 100;
+
+// A step ends, when its changes pause for longer than this.
+const STEP_GAP: Duration = Duration::from_millis(500);
+
+/// The kinds of changes, which are recorded in the history. Consecutive changes of the same kind
+/// within one gesture are merged into one step, so e.g. holding a key is undone at once.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StepKind {
+    Rotate,
+    Scale,
+    Move,
+    Camera,
+    /// A jump of the camera or the object into another state, like a standard view, a bookmark or
+    /// a reset. Jumps are never merged.
+    Jump,
+}
+
+/// The history of the camera and the object transform. It keeps the states before each step, so
+/// the steps may be undone and redone.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    undo: Vec<Bookmark>,
+    redo: Vec<Bookmark>,
+    /// The kind of the step, which is still open, and the time of its last change.
+    open: Option<(StepKind, Instant)>,
+}
+impl History {
+    /// Records the state before a change. If the change continues the open step, the state is
+    /// already recorded. The step stays open, until the gesture ends or the changes pause.
+    ///
+    /// # Arguments
+    ///
+    /// * 'kind' - The kind of the change.
+    /// * 'state' - The state before the change.
+    pub fn record(&mut self, kind: StepKind, state: Bookmark) {
+        self.record_at(kind, state, Instant::now());
+    }
+
+    /// Records the state before a change, which happens at the provided time.
+    fn record_at(&mut self, kind: StepKind, state: Bookmark, now: Instant) {
+        if let Some((open, last)) = self.open {
+            if open == kind && kind != StepKind::Jump && now.duration_since(last) < STEP_GAP {
+                self.open = Some((kind, now));
+                return;
+            }
+        }
+        self.undo.push(state);
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        self.open = Some((kind, now));
+    }
+
+    /// Closes the open step, e.g. when the key or the mouse button of a gesture is released. The
+    /// next change starts a step of its own.
+    pub fn end_step(&mut self) {
+        self.open = None;
+    }
+
+    /// Takes the state before the last step. The current state is kept to redo the step.
+    pub fn undo(&mut self, current: Bookmark) -> Option<Bookmark> {
+        let state = self.undo.pop()?;
+        self.redo.push(current);
+        self.open = None;
+        Some(state)
+    }
+
+    /// Takes the state after the last undone step. The current state is kept to undo it again.
+    pub fn redo(&mut self, current: Bookmark) -> Option<Bookmark> {
+        let state = self.redo.pop()?;
+        self.undo.push(current);
+        self.open = None;
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A state, which is told apart by its scaling factor.
+    fn state(scaling_factor: f32) -> Bookmark {
+        Bookmark { view_position: [0.0; 3], view_direction: [0.0, 0.0, 1.0], up: [0.0, 1.0, 0.0], rot: [0.0; 3],
+                   scaling_factor, object_position: [0.0; 3] }
+    }
+
+    #[test]
+    fn merges_changes_of_one_kind_within_the_gap() {
+        let mut history = History::default();
+        let start = Instant::now();
+        history.record_at(StepKind::Scale, state(1.0), start);
+        history.record_at(StepKind::Scale, state(2.0), start + STEP_GAP / 2);
+        history.record_at(StepKind::Scale, state(3.0), start + STEP_GAP);
+        assert_eq!(history.undo(state(4.0)), Some(state(1.0)));
+        assert_eq!(history.undo(state(1.0)), None);
+    }
+
+    #[test]
+    fn starts_a_new_step_after_a_pause_another_kind_or_the_end_of_the_gesture() {
+        let mut history = History::default();
+        let start = Instant::now();
+        history.record_at(StepKind::Scale, state(1.0), start);
+        history.record_at(StepKind::Scale, state(2.0), start + STEP_GAP * 2);
+        history.record_at(StepKind::Rotate, state(3.0), start + STEP_GAP * 2);
+        history.end_step();
+        history.record_at(StepKind::Rotate, state(4.0), start + STEP_GAP * 2);
+        for expected in [4.0, 3.0, 2.0, 1.0] {
+            assert_eq!(history.undo(state(0.0)).map(|state| state.scaling_factor), Some(expected));
+        }
+    }
+
+    #[test]
+    fn never_merges_jumps() {
+        let mut history = History::default();
+        let start = Instant::now();
+        history.record_at(StepKind::Jump, state(1.0), start);
+        history.record_at(StepKind::Jump, state(2.0), start);
+        assert_eq!(history.undo(state(3.0)), Some(state(2.0)));
+        assert_eq!(history.undo(state(2.0)), Some(state(1.0)));
+    }
+
+    #[test]
+    fn redoes_in_the_reverse_order_of_undo() {
+        let mut history = History::default();
+        history.record(StepKind::Jump, state(1.0));
+        history.record(StepKind::Jump, state(2.0));
+        assert_eq!(history.undo(state(3.0)), Some(state(2.0)));
+        assert_eq!(history.undo(state(2.0)), Some(state(1.0)));
+        assert_eq!(history.redo(state(1.0)), Some(state(2.0)));
+        assert_eq!(history.redo(state(2.0)), Some(state(3.0)));
+        assert_eq!(history.redo(state(3.0)), None);
+        assert_eq!(history.undo(state(3.0)), Some(state(2.0)));
+
+        // A new change drops the steps, which could be redone.
+        history.record(StepKind::Jump, state(5.0));
+        assert_eq!(history.redo(state(6.0)), None);
+    }
+}
@@ -1,117 +1,509 @@
-use glium::{Display, Frame};
-use glium_glyph::{GlyphBrush, glyph_brush::{ab_glyph::FontRef, Text, Section, Layout, HorizontalAlign}, GlyphBrushBuilder};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 
+use glium::{Display, Program, Surface, VertexBuffer};
+use glium::index::{NoIndices, PrimitiveType};
+use glium_glyph::{GlyphBrush, glyph_brush::{ab_glyph::FontArc, GlyphCruncher, Text, Section, Layout, HorizontalAlign},
+                  GlyphBrushBuilder};
+use nalgebra::Vector3;
+
+use crate::layout::{Anchor, HudLayout, Rect};
+use crate::measure::MeasureMode;
 use crate::model::Model;
+use crate::navigation;
+use crate::performance::{DrawStats, Performance, GRAPH_FRAMES};
+use crate::view::Viewport;
+
+pub const FONT_SIZE: f32 = 18.0;
+
+// The fonts, which are bundled with the application.
+const MONO_FONT: &[u8] = include_bytes!("../fonts/NotoMonoNerdFontMono-Regular.ttf");
+const SERIF_FONT: &[u8] = include_bytes!("../fonts/NotoSerifNerdFont-Light.ttf");
 
-const FONT_SIZE: f32 = 18.0;
+// The space between the text and the border of its panel in pixels.
+const PANEL_PADDING: f32 = 4.0;
+// The space between the panels and between the panels and the border of the screen in pixels.
+const PANEL_SPACING: f32 = 4.0;
+// The help is split into at most this number of columns.
+const MAX_HELP_COLUMNS: usize = 4;
+// The space between the keys and their descriptions, and between the columns of the help, in
+// multiples of the font size.
+const HELP_KEY_SPACING: f32 = 1.0;
+const HELP_COLUMN_SPACING: f32 = 2.0;
+// The murky white of the panels behind dark text, and the murky black behind bright text.
+const PANEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.6];
+const DARK_PANEL_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+// The lines of output, which the console shows above its input.
+const CONSOLE_LINES: usize = 8;
+// The size of one bar of the frame time graph and the height of the graph in pixels.
+const GRAPH_BAR_WIDTH: f32 = 2.0;
+const GRAPH_HEIGHT: f32 = 40.0;
+// The frame time in seconds, which fills the height of the graph.
+const GRAPH_RANGE: f32 = 0.05;
 
-/// The Vertexes of the HUD will be stored in this structure, grabbed from the triangle example
-/// :-).
+/// A text and the position of its top left corner in pixels.
+type PlacedText = (String, (f32, f32));
+
+/// A corner of a panel of the HUD in pixels.
 #[derive(Copy, Clone)]
 struct Vertex {
-    position: [f32; 3],
+    position: [f32; 2],
 }
 implement_vertex!(Vertex, position);
 
+/// The font of the HUD: One of the bundled fonts or a TTF file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HudFont {
+    Mono,
+    Serif,
+    File(PathBuf),
+}
+impl HudFont {
+    /// Loads the font. A file, which cannot be read, is reported and replaced by the mono font.
+    fn load(&self) -> FontArc {
+        let font = match self {
+            HudFont::Mono => FontArc::try_from_slice(MONO_FONT).map_err(|error| error.to_string()),
+            HudFont::Serif => FontArc::try_from_slice(SERIF_FONT).map_err(|error| error.to_string()),
+            HudFont::File(path) => std::fs::read(path)
+                .map_err(|error| error.to_string())
+                .and_then(|bytes| FontArc::try_from_vec(bytes).map_err(|error| error.to_string())),
+        };
+        font.unwrap_or_else(|error| {
+            log::error!("[HudFont::load()] Cannot load the font {self:?}: {error}");
+            FontArc::try_from_slice(MONO_FONT).unwrap()
+        })
+    }
+}
+impl FromStr for HudFont {
+    type Err = String;
+
+    /// Parses "mono", "serif" or the path of a font file.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mono" => Ok(HudFont::Mono),
+            "serif" => Ok(HudFont::Serif),
+            _ if Path::new(s).is_file() => Ok(HudFont::File(PathBuf::from(s))),
+            _ => Err(format!("Unknown font '{s}', expected mono, serif or the path of a TTF file")),
+        }
+    }
+}
+
+/// The look of the text in the HUD.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HudStyle {
+    pub font: HudFont,
+    /// The size of the font in logical pixels. It grows with the scale factor of the window.
+    pub font_size: f32,
+    /// The color of the text in RGBA.
+    pub color: [f32; 4],
+}
+impl Default for HudStyle {
+    fn default() -> Self {
+        Self { font: HudFont::Mono, font_size: FONT_SIZE, color: [0.0, 0.0, 0.0, 1.0] }
+    }
+}
+impl HudStyle {
+    /// The color of the panels, which gives the most contrast to the text.
+    fn panel_color(&self) -> [f32; 4] {
+        let [r, g, b, _] = self.color;
+        if 0.299 * r + 0.587 * g + 0.114 * b > 0.5 { DARK_PANEL_COLOR } else { PANEL_COLOR }
+    }
+}
+
 /// The Head-Up-Display of the application. The HUD presents numeric informations regarding the
 /// position of the object and the viewers position and direction. It has a murky white background
 /// to improve the contrast between text and background/object.
 pub struct HudView {
-    glyph_brush: GlyphBrush<'static, FontRef<'static>>,
+    glyph_brush: GlyphBrush<'static, FontArc>,
+    style: HudStyle,
+    labels: Vec<PlacedText>,
+    help: Vec<(String, String)>,
+    /// The time to send the object to the graphics card.
+    upload_time: Duration,
+    program: Program,
 }
 impl HudView {
-    pub fn new(display: &Display) -> Self {
-        let dejavu: &[u8] = include_bytes!("../fonts/NotoMonoNerdFontMono-Regular.ttf");
-        let dejavu_font = FontRef::try_from_slice(dejavu).unwrap();
+    /// Creates the HUD with the font, size and color of the provided style.
+    pub fn new(display: &Display, style: &HudStyle) -> Self {
+        let glyph_brush = GlyphBrushBuilder::using_font(style.font.load()).build(display);
 
-        let glyph_brush = GlyphBrushBuilder::using_font(dejavu_font).build(display);
+        let vertex_shader_src = include_str!("hud.vertex.glsl");
+        let fragment_shader_src = include_str!("hud.fragment.glsl");
+        let program = Program::from_source(display, vertex_shader_src, fragment_shader_src,
+                                           None).unwrap();
 
-        // TODO: Create the shaders for the background of the HUD
-        // TODO: Create the geometry for the background of the HUD
-        // let top_left = Vertex { position: [-1.0, 1.0, 1.0] };
-        // let top_right = Vertex { position: [1.0, 1.0, 1.0] };
-        // let bottom_left = Vertex { position: [-1.0, 0.9, 1.0] };
-        // let bottom_right = Vertex { position: [1.0, 0.9, 1.0] };
-
-        HudView { glyph_brush }
+        HudView { glyph_brush, style: style.clone(), labels: Vec::new(), help: Vec::new(), upload_time: Duration::ZERO, program }
     }
 
     /// Draws all information of the Hud on the provided target. The provided display is also
-    /// needed for the draw_queued call of the framework.
-    pub fn draw(
+    /// needed for the draw_queued call of the framework. The HUD is laid out in the pixels of the
+    /// complete picture, which might be larger than the target.
+    pub fn draw<S: Surface>(
         &mut self,
-        target: &mut Frame,
+        target: &mut S,
         display: &Display,
-        model: &Model
+        model: &Model,
+        viewport: &Viewport,
+        performance: &Performance,
+        stats: &mut DrawStats,
     ) {
-        let screen_dims = display.get_framebuffer_dimensions();
-
-        // TODO: Recalculate the dimesions of the top HUD
-        // TODO: Recalculate the dimesions of the bottom HUD
+        let screen_dims = (viewport.width, viewport.height);
+        let font_size = self.style.font_size * viewport.scale;
 
         log::debug!("[View::draw_head_up_display()] screen_dims=({}, {})", screen_dims.0, screen_dims.1);
 
-        self.draw_hud_background(target);
+        // The labels of the annotations
+        for (text, position) in self.labels.drain(..) {
+            self.glyph_brush.queue(
+                Section::default()
+                    .add_text(Text::new(&text).with_scale(font_size).with_color(self.style.color))
+                    .with_screen_position((position.0, position.1))
+                    .with_layout(Layout::default().h_align(HorizontalAlign::Center))
+                );
+        }
 
-        // Top Left Corner (Coordinates of the object)
-        let coordinates = format!("(x={}, y={}, z={})\n(rx={}, ry={}, rz={})",
-                                model.object_position[0], model.object_position[1], model.object_position[2],
-                                model.rot[0], model.rot[1], model.rot[2]);
-        self.glyph_brush.queue(
-            Section::default()
-                .add_text(Text::new(&coordinates).with_scale(FONT_SIZE))
-                .with_bounds((screen_dims.0 as f32, screen_dims.1 as f32 / 2.0)),
-        );
+        let mut layout = HudLayout::new(screen_dims.0 as f32, screen_dims.1 as f32, PANEL_SPACING * viewport.scale);
+        let mut bars = Vec::new();
+        let console = if model.console.open { Some(self.queue_console(&mut layout, model, font_size)) } else { None };
+        let mut panels = if model.show_help && !self.help.is_empty() {
+            vec![self.queue_help(&layout, font_size)]
+        } else {
+            let mut panels = self.queue_status(&mut layout, model, font_size);
+            if model.show_performance_section {
+                let (panel, graph) = self.queue_performance(&mut layout, model, performance, font_size);
+                panels.push(panel);
+                bars = graph;
+            }
+            panels
+        };
+        panels.extend(console);
 
-        // Top Right Corner
-        let view_coordinates = format!("(x={}, y={}, z={})",
-                                model.view_position[0], model.view_position[1], model.view_position[2]);
-        self.glyph_brush.queue(
-            Section::default()
-                .add_text(Text::new(&view_coordinates).with_scale(FONT_SIZE))
-                .with_screen_position((screen_dims.0 as f32, 0.0))
-                .with_bounds((screen_dims.0 as f32, screen_dims.1 as f32))
-                .with_layout(Layout::default().h_align(HorizontalAlign::Right))
-            );
+        // The panels are drawn first, so the text of the panels and the labels lie on top.
+        self.draw_rectangles(target, display, &panels, self.style.panel_color(), viewport, stats);
+        // The graph has the color of the text, so it stands out from the panel like the text.
+        let [r, g, b, _] = self.style.color;
+        self.draw_rectangles(target, display, &bars, [r, g, b, 0.8], viewport, stats);
 
-        // Middle Chenter
-        // self.glyph_brush.queue(
-        //     Section::default()
-        //         .add_text(Text::new("This is in the middle of the screen").with_scale(48.0))
-        //         .with_screen_position((screen_dims.0 as f32 / 2.0, screen_dims.1 as f32 / 2.0))
-        //         .with_bounds((screen_dims.0 as f32, screen_dims.1 as f32))
-        //         .with_layout(
-        //             Layout::default()
-        //                 .h_align(HorizontalAlign::Center)
-        //                 .v_align(VerticalAlign::Center),
-        //         )
-        // );
-
-        // Bottom Left Corner (One line only!)
-        let scaling_factor = format!("Scaling factor: {}", model.scaling_factor);
-        self.glyph_brush.queue(
-            Section::default()
-                .add_text(Text::new(&scaling_factor).with_scale(FONT_SIZE))
-                .with_screen_position((0.0, screen_dims.1 as f32 - (FONT_SIZE + 1.0)))
-            );
+        // let mut target: Frame = display.draw();
+        self.glyph_brush.draw_queued_with_transform(viewport.hud_transform(), display, target);
+        stats.count(0);
 
-        // Bottom Right Corner
-        let direction = format!("Direction ({}, {}, {})",
-                                model.view_direction[0], model.view_direction[1], model.view_direction[2]);
-        self.glyph_brush.queue(
-            Section::default()
-                .add_text(Text::new(&direction).with_scale(FONT_SIZE))
-                .with_screen_position((screen_dims.0 as f32, screen_dims.1 as f32 - (FONT_SIZE + 1.0)))
-                .with_layout(Layout::default().h_align(HorizontalAlign::Right))
+        // target.finish().unwrap();
+    }
+
+    /// Queues the panels with the state of the object and the viewer in the corners of the screen
+    /// and returns their rectangles.
+    fn queue_status(&mut self, layout: &mut HudLayout, model: &Model, font_size: f32) -> Vec<Rect> {
+        let mut panels = Vec::new();
+
+        let format = &model.hud_format;
+
+        // Top Left Corner (The object)
+        if model.show_object_section {
+            let object = format!("Object\nPosition {}\nRotation ({}, {}, {})\nScale {}",
+                                 format.position(&model.object_position.into(), model.model_unit, model.unit),
+                                 format.angle(model.rot[0]), format.angle(model.rot[1]), format.angle(model.rot[2]),
+                                 format.scale(model.scaling_factor));
+            panels.push(self.queue_panel(layout, Anchor::TopLeft, &object, font_size));
+        }
+
+        // Below the object (The loaded mesh). The lower left corner belongs to the gizmo.
+        if model.show_statistics_section {
+            let statistics = &model.statistics;
+            let file_name = Path::new(&model.file_name).file_name()
+                .map_or_else(|| model.file_name.clone(), |name| name.to_string_lossy().into_owned());
+            let mesh = format!("Mesh {}\nVertices {} (positions {})\nTriangles {}\n\
+                                Objects {}, groups {}, materials {}\nSize {}\nLoaded in {:.1} ms (upload {:.1} ms)",
+                               file_name, statistics.vertices, statistics.positions, statistics.triangles,
+                               statistics.objects, statistics.groups, statistics.materials,
+                               format.position(&model.bounds.size().into(), model.model_unit, model.unit),
+                               statistics.load_time.as_secs_f64() * 1000.0, self.upload_time.as_secs_f64() * 1000.0);
+            panels.push(self.queue_panel(layout, Anchor::TopLeft, &mesh, font_size));
+        }
+
+        // Below the mesh (The picked triangle)
+        if let Some(pick) = model.current_pick() {
+            let picked = format!("Group: {}\nTriangle {} (vertices {}, {}, {})\nPosition {}\nNormal {}",
+                                 pick.group.as_deref().unwrap_or("-"),
+                                 pick.triangle, pick.vertices[0], pick.vertices[1], pick.vertices[2],
+                                 format.position(&pick.position.into(), model.model_unit, model.unit),
+                                 format.direction(&pick.normal.into()));
+            panels.push(self.queue_panel(layout, Anchor::TopLeft, &picked, font_size));
+        }
+
+        // Top Right Corner (The camera)
+        if model.show_camera_section {
+            let camera = format!("Camera\n{:?} {:?} {}\nPosition {}", model.navigation, model.projection,
+                                 format.angle(model.lens.field_of_view),
+                                 format.position(&model.view_position, model.model_unit, model.unit));
+            panels.push(self.queue_panel(layout, Anchor::TopRight, &camera, font_size));
+        }
+
+        // Below the camera (The measurements)
+        if model.measure_mode != MeasureMode::Off || !model.measurements.is_empty() {
+            let mut measurements = match model.measure_mode {
+                MeasureMode::Off => String::from("Measurements"),
+                mode => format!("Measure {:?} ({}/{})", mode, model.measure_points.len(), mode.points()),
+            };
+            for (i, measurement) in model.measurements.iter().enumerate() {
+                measurements.push_str(&format!("\n{}: {}", i + 1, measurement.label(model)));
+            }
+            panels.push(self.queue_panel(layout, Anchor::TopRight, &measurements, font_size));
+        }
+
+        // Bottom Right Corner (The line of sight)
+        if model.show_direction_section {
+            let (yaw, pitch) = navigation::yaw_pitch(&Vector3::from(model.view_direction));
+            let direction = format!("Direction\nYaw {} Pitch {}\n{}", format.angle(yaw), format.angle(pitch),
+                                    format.direction(&model.view_direction));
+            panels.push(self.queue_panel(layout, Anchor::BottomRight, &direction, font_size));
+        }
+        panels
+    }
+
+    /// Queues the console across the top of the screen and returns its rectangle. It shows the
+    /// last lines of the output above the command, which is typed.
+    fn queue_console(&mut self, layout: &mut HudLayout, model: &Model, font_size: f32) -> Rect {
+        let mut text = String::new();
+        for line in model.console.output(CONSOLE_LINES) {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text.push_str(&format!("> {}_", model.console.input));
+        self.queue_panel_with(&text, font_size, (0.0, 0.0), |_, height| layout.place_top(height))
+    }
+
+    /// Queues the help with the key bindings in the middle of the screen and returns its
+    /// rectangle. The help is split into as many columns, as it needs to be readable. If it is
+    /// still too large for the screen, the font is shrunk.
+    fn queue_help(&mut self, layout: &HudLayout, font_size: f32) -> Rect {
+        let padding = PANEL_PADDING * font_size / FONT_SIZE;
+        let (available_width, available_height) = layout.available();
+        let mut best: Option<(f32, usize)> = None;
+        for columns in 1..=MAX_HELP_COLUMNS {
+            let (_, (width, height)) = self.help_sections(columns, font_size);
+            if width <= 0.0 || height <= 0.0 {
+                continue;
+            }
+            let fit = ((available_width - 2.0 * padding) / width)
+                .min((available_height - 2.0 * padding) / height)
+                .min(1.0);
+            if best.is_none_or(|(best_fit, _)| fit > best_fit) {
+                best = Some((fit, columns));
+            }
+        }
+        let (fit, columns) = match best {
+            Some(best) => best,
+            None => return layout.center(0.0, 0.0),
+        };
+        let font_size = font_size * fit.max(0.0);
+        let padding = PANEL_PADDING * font_size / FONT_SIZE;
+        let (sections, (width, height)) = self.help_sections(columns, font_size);
+        let panel = layout.center(width + 2.0 * padding, height + 2.0 * padding);
+        for (text, (x, y)) in sections {
+            self.glyph_brush.queue(
+                Section::default()
+                    .add_text(Text::new(&text).with_scale(font_size).with_color(self.style.color))
+                    .with_screen_position((panel.x + padding + x, panel.y + padding + y))
             );
+        }
+        panel
+    }
 
-        // let mut target: Frame = display.draw();
-        self.glyph_brush.draw_queued(display, target);
+    /// Lays out the help in columns. The keys and the descriptions of each column are separate
+    /// texts, which are placed by their measured widths, so they line up with any font. The texts
+    /// start with an empty line for the title, so their lines match the lines of the title.
+    ///
+    /// # Arguments
+    ///
+    /// * 'columns' - The number of columns.
+    /// * 'font_size' - The font size in pixels.
+    ///
+    /// Returns the texts with their positions relative to the top left corner of the help, and the
+    /// size of the help.
+    fn help_sections(&mut self, columns: usize, font_size: f32) -> (Vec<PlacedText>, (f32, f32)) {
+        let rows = self.help.len().div_ceil(columns).max(1);
+        let texts: Vec<(String, String)> = self.help.chunks(rows)
+            .map(|column| {
+                let keys: Vec<&str> = column.iter().map(|(keys, _)| keys.as_str()).collect();
+                let descriptions: Vec<&str> = column.iter().map(|(_, description)| description.as_str()).collect();
+                (format!("\n{}", keys.join("\n")), format!("\n{}", descriptions.join("\n")))
+            })
+            .collect();
 
-        // target.finish().unwrap();
+        let title = String::from("Key bindings");
+        let (mut width, mut height) = self.text_size(&title, font_size);
+        let mut sections = vec![(title, (0.0, 0.0))];
+        let mut x = 0.0;
+        for (i, (keys, descriptions)) in texts.into_iter().enumerate() {
+            if i > 0 {
+                x += HELP_COLUMN_SPACING * font_size;
+            }
+            let (keys_width, keys_height) = self.text_size(&keys, font_size);
+            let (descriptions_width, descriptions_height) = self.text_size(&descriptions, font_size);
+            let description_x = x + keys_width + HELP_KEY_SPACING * font_size;
+            sections.push((keys, (x, 0.0)));
+            sections.push((descriptions, (description_x, 0.0)));
+            x = description_x + descriptions_width;
+            width = width.max(x);
+            height = height.max(keys_height).max(descriptions_height);
+        }
+        (sections, (width, height))
+    }
+
+    /// The extent of a text, which starts at the top left corner, in pixels.
+    fn text_size(&mut self, text: &str, font_size: f32) -> (f32, f32) {
+        let section = Section::default().add_text(Text::new(text).with_scale(font_size));
+        self.glyph_brush.glyph_bounds(&section).map_or((0.0, 0.0), |bounds| (bounds.max.x, bounds.max.y))
+    }
+
+    /// Queues the panel with the frame timing and returns the rectangle of the panel together with
+    /// the bars of the frame time graph. Each bar is the time between two frames, the thin line
+    /// marks 60 frames per second.
+    ///
+    /// # Arguments
+    ///
+    /// * 'layout' - The layout, which places the panel.
+    /// * 'model' - The model, which decides, whether the rendering is continuous.
+    /// * 'performance' - The measurements of the last frames.
+    /// * 'font_size' - The font size in pixels.
+    fn queue_performance(&mut self, layout: &mut HudLayout, model: &Model, performance: &Performance,
+                         font_size: f32) -> (Rect, Vec<Rect>) {
+        let gpu_time = match performance.gpu_time {
+            Some(gpu_time) => format!("{:.2} ms", gpu_time.as_secs_f64() * 1000.0),
+            None => String::from("n/a"),
+        };
+        // The frame rate is only meaningful, while the frames follow each other without a pause.
+        let fps = if model.render_continuously { format!("\nFPS {:.1}", performance.fps()) } else { String::new() };
+        let text = format!("Performance ({}){fps}\nCPU {:.2} ms, object GPU {}\nDraw calls {}, triangles {}",
+                           if model.render_continuously { "continuous" } else { "on change" },
+                           performance.cpu_time.as_secs_f64() * 1000.0, gpu_time,
+                           performance.draw.draw_calls, performance.draw.triangles);
+
+        let scale = font_size / FONT_SIZE;
+        let (bar_width, graph_height) = (GRAPH_BAR_WIDTH * scale, GRAPH_HEIGHT * scale);
+        let graph_width = bar_width * GRAPH_FRAMES as f32;
+        let panel = self.queue_panel_with(&text, font_size, (graph_width, graph_height),
+                                          |width, height| layout.place(Anchor::BottomRight, width, height));
+        let padding = PANEL_PADDING * scale;
+        let left = panel.x + padding;
+        let bottom = panel.y + panel.height - padding;
+
+        let mut bars: Vec<Rect> = performance.frame_times().iter().rev().enumerate()
+            .map(|(i, seconds)| {
+                let height = (seconds / GRAPH_RANGE).min(1.0) * graph_height;
+                Rect { x: left + graph_width - (i + 1) as f32 * bar_width, y: bottom - height, width: bar_width, height }
+            })
+            .collect();
+        let target = graph_height / (GRAPH_RANGE * 60.0);
+        bars.push(Rect { x: left, y: bottom - target, width: graph_width, height: scale });
+        (panel, bars)
+    }
+
+    /// Sets the time, which was needed to send the object to the graphics card. It is shown
+    /// together with the load time of the object.
+    pub fn set_upload_time(&mut self, upload_time: Duration) {
+        self.upload_time = upload_time;
+    }
+
+    /// Replaces the keys and descriptions, which are shown in the help.
+    pub fn set_help(&mut self, help: Vec<(String, String)>) {
+        self.help = help;
+    }
+
+    /// Queues a label at a position on the screen. Labels are used to annotate the object and are
+    /// drawn together with the rest of the HUD.
+    ///
+    /// # Arguments
+    ///
+    /// * 'text' - The text of the label.
+    /// * 'position' - The position of the label in pixels, starting at the top left corner.
+    pub fn queue_label(&mut self, text: &str, position: (f32, f32)) {
+        self.labels.push((text.to_string(), position));
+    }
+
+    /// Drops the queued labels, when the HUD is not drawn.
+    pub fn discard(&mut self) {
+        self.labels.clear();
+    }
+
+    /// Queues the text of a panel and returns the rectangle of the panel. The size of the panel
+    /// is measured from the bounds of the glyphs.
+    ///
+    /// # Arguments
+    ///
+    /// * 'layout' - The layout, which places the panel.
+    /// * 'anchor' - The corner of the panel.
+    /// * 'text' - The text of the panel, which may have several lines.
+    /// * 'font_size' - The font size in pixels.
+    fn queue_panel(&mut self, layout: &mut HudLayout, anchor: Anchor, text: &str, font_size: f32) -> Rect {
+        self.queue_panel_with(text, font_size, (0.0, 0.0), |width, height| layout.place(anchor, width, height))
+    }
+
+    /// Queues the text of a panel, which is placed by the provided function, and returns the
+    /// rectangle of the panel.
+    ///
+    /// # Arguments
+    ///
+    /// * 'text' - The text of the panel, which may have several lines.
+    /// * 'font_size' - The font size in pixels.
+    /// * 'below' - The width and height of the space, which is kept free below the text.
+    /// * 'place' - Finds the rectangle of the panel from its width and height.
+    fn queue_panel_with<F>(&mut self, text: &str, font_size: f32, below: (f32, f32), place: F) -> Rect
+        where F: FnOnce(f32, f32) -> Rect {
+        let padding = PANEL_PADDING * font_size / FONT_SIZE;
+        let section = Section::default()
+            .add_text(Text::new(text).with_scale(font_size).with_color(self.style.color));
+        let bounds = match self.glyph_brush.glyph_bounds(&section) {
+            Some(bounds) => bounds,
+            None => return place(0.0, 0.0),
+        };
+        let (width, height) = if below.1 > 0.0 {
+            (bounds.width().max(below.0), bounds.height() + padding + below.1)
+        } else {
+            (bounds.width(), bounds.height())
+        };
+        let panel = place(width + 2.0 * padding, height + 2.0 * padding);
+        self.glyph_brush.queue(
+            section.with_screen_position((panel.x + padding - bounds.min.x, panel.y + padding - bounds.min.y))
+        );
+        panel
     }
 
-    fn draw_hud_background(&self, _target: &Frame) {
-        // TODO: Render the background of the HUD
+    /// Draws translucent rectangles, like the panels behind the text of the HUD.
+    ///
+    /// # Arguments
+    ///
+    /// * 'target' - The surface to draw on.
+    /// * 'display' - The display, which creates the vertex buffer.
+    /// * 'rectangles' - The rectangles in pixels.
+    /// * 'color' - The color of the rectangles including their opacity.
+    /// * 'viewport' - The viewport, which maps the pixels onto the target.
+    /// * 'stats' - Counts the draw call.
+    fn draw_rectangles<S: Surface>(&self, target: &mut S, display: &Display, rectangles: &[Rect], color: [f32; 4],
+                                   viewport: &Viewport, stats: &mut DrawStats) {
+        let vertices: Vec<Vertex> = rectangles.iter()
+            .filter(|panel| panel.width > 0.0 && panel.height > 0.0)
+            .flat_map(|panel| {
+                let (left, top) = (panel.x, panel.y);
+                let (right, bottom) = (panel.x + panel.width, panel.y + panel.height);
+                [[left, top], [right, top], [left, bottom], [left, bottom], [right, top], [right, bottom]]
+            })
+            .map(|position| Vertex { position })
+            .collect();
+        if vertices.is_empty() {
+            return;
+        }
+
+        let uniforms = uniform! { transform: viewport.hud_transform(), background: color };
+        let params = glium::DrawParameters {
+            blend: glium::Blend::alpha_blending(),
+            .. Default::default()
+        };
+        let vertex_buffer = VertexBuffer::new(display, &vertices).unwrap();
+        target.draw(&vertex_buffer, NoIndices(PrimitiveType::TrianglesList), &self.program, &uniforms, &params)
+            .unwrap();
+        stats.count(vertices.len() / 3);
     }
 }
@@ -0,0 +1,10 @@
+#version 150
+
+in vec2 position;
+
+uniform mat4 transform;
+
+// The panels of the HUD are given in pixels, starting at the top left corner.
+void main() {
+    gl_Position = transform * vec4(position, 0.0, 1.0);
+}
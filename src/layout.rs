@@ -0,0 +1,137 @@
+/// The corner of the screen, at which a panel of the HUD is placed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+impl Anchor {
+    const ALL: [Anchor; 4] = [Anchor::TopLeft, Anchor::TopRight, Anchor::BottomLeft, Anchor::BottomRight];
+
+    fn is_right(&self) -> bool {
+        matches!(self, Anchor::TopRight | Anchor::BottomRight)
+    }
+
+    fn is_bottom(&self) -> bool {
+        matches!(self, Anchor::BottomLeft | Anchor::BottomRight)
+    }
+}
+
+/// A rectangle on the screen in pixels, starting at the top left corner.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Places the panels of the HUD in the corners of the screen. The panels of one corner are stacked
+/// in the order, in which they are placed: At the top corners downwards, at the bottom corners
+/// upwards. So a new panel only needs its size, the layout finds its position.
+pub struct HudLayout {
+    width: f32,
+    height: f32,
+    spacing: f32,
+    // The space, which is already taken at each corner, in the order of Anchor::ALL.
+    taken: [f32; 4],
+}
+impl HudLayout {
+    /// Creates an empty layout for a screen.
+    ///
+    /// # Arguments
+    ///
+    /// * 'width', 'height' - The size of the screen in pixels.
+    /// * 'spacing' - The space between the panels and between the panels and the border.
+    pub fn new(width: f32, height: f32, spacing: f32) -> Self {
+        Self { width, height, spacing, taken: [spacing; 4] }
+    }
+
+    /// Places a panel in a corner below or above the panels, which are already there, and returns
+    /// its rectangle.
+    ///
+    /// # Arguments
+    ///
+    /// * 'anchor' - The corner of the panel.
+    /// * 'width', 'height' - The size of the panel in pixels.
+    pub fn place(&mut self, anchor: Anchor, width: f32, height: f32) -> Rect {
+        let corner = Anchor::ALL.iter().position(|a| *a == anchor).unwrap();
+        let x = if anchor.is_right() { self.width - self.spacing - width } else { self.spacing };
+        let y = if anchor.is_bottom() {
+            self.height - self.taken[corner] - height
+        } else {
+            self.taken[corner]
+        };
+        self.taken[corner] += height + self.spacing;
+        Rect { x, y, width, height }
+    }
+
+    /// Places a panel across the whole width of the screen above the panels of the top corners,
+    /// which are moved down, and returns its rectangle. This must happen before the panels of the
+    /// top corners are placed.
+    ///
+    /// # Arguments
+    ///
+    /// * 'height' - The height of the panel in pixels.
+    pub fn place_top(&mut self, height: f32) -> Rect {
+        let y = self.taken[0].max(self.taken[1]);
+        let panel = Rect { x: self.spacing, y, width: self.width - 2.0 * self.spacing, height };
+        self.taken[0] = y + height + self.spacing;
+        self.taken[1] = self.taken[0];
+        panel
+    }
+
+    /// The space inside the border of the screen, which is available for a panel.
+    pub fn available(&self) -> (f32, f32) {
+        (self.width - 2.0 * self.spacing, self.height - 2.0 * self.spacing)
+    }
+
+    /// Places a panel in the middle of the screen, on top of the other panels, and returns its
+    /// rectangle.
+    ///
+    /// # Arguments
+    ///
+    /// * 'width', 'height' - The size of the panel in pixels.
+    pub fn center(&self, width: f32, height: f32) -> Rect {
+        Rect { x: (self.width - width) / 2.0, y: (self.height - height) / 2.0, width, height }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    #[test]
+    fn stacks_panels_away_from_each_corner() {
+        let mut layout = HudLayout::new(800.0, 600.0, 4.0);
+        assert_eq!(layout.place(Anchor::TopLeft, 100.0, 50.0), rect(4.0, 4.0, 100.0, 50.0));
+        assert_eq!(layout.place(Anchor::TopLeft, 120.0, 30.0), rect(4.0, 58.0, 120.0, 30.0));
+        assert_eq!(layout.place(Anchor::TopRight, 100.0, 50.0), rect(696.0, 4.0, 100.0, 50.0));
+        assert_eq!(layout.place(Anchor::TopRight, 80.0, 20.0), rect(716.0, 58.0, 80.0, 20.0));
+        assert_eq!(layout.place(Anchor::BottomLeft, 100.0, 50.0), rect(4.0, 546.0, 100.0, 50.0));
+        assert_eq!(layout.place(Anchor::BottomLeft, 100.0, 40.0), rect(4.0, 502.0, 100.0, 40.0));
+        assert_eq!(layout.place(Anchor::BottomRight, 100.0, 50.0), rect(696.0, 546.0, 100.0, 50.0));
+        assert_eq!(layout.place(Anchor::BottomRight, 60.0, 10.0), rect(736.0, 532.0, 60.0, 10.0));
+    }
+
+    #[test]
+    fn moves_both_top_corners_below_the_top_panel() {
+        let mut layout = HudLayout::new(800.0, 600.0, 4.0);
+        assert_eq!(layout.place_top(100.0), rect(4.0, 4.0, 792.0, 100.0));
+        assert_eq!(layout.place(Anchor::TopLeft, 100.0, 50.0), rect(4.0, 108.0, 100.0, 50.0));
+        assert_eq!(layout.place(Anchor::TopRight, 100.0, 50.0), rect(696.0, 108.0, 100.0, 50.0));
+        assert_eq!(layout.place(Anchor::BottomLeft, 100.0, 50.0), rect(4.0, 546.0, 100.0, 50.0));
+    }
+
+    #[test]
+    fn centers_panels_inside_the_border() {
+        let layout = HudLayout::new(800.0, 600.0, 4.0);
+        assert_eq!(layout.available(), (792.0, 592.0));
+        assert_eq!(layout.center(200.0, 100.0), rect(300.0, 250.0, 200.0, 100.0));
+    }
+}
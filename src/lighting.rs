@@ -0,0 +1,267 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use glium::uniforms::{UniformValue, Uniforms};
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+use crate::navigation;
+
+/// The number of lights, the shader of the object handles.
+pub const MAX_LIGHTS: usize = 4;
+
+/// A directional light shines from far away, so its rays are parallel. A point light shines from a
+/// position in the scene.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LightKind {
+    Directional,
+    Point,
+}
+
+/// A light of the scene.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Light {
+    pub kind: LightKind,
+    /// The direction, from which a directional light falls, or the position of a point light.
+    pub vector: [f32; 3],
+    pub color: [f32; 3],
+    pub intensity: f32,
+}
+impl Light {
+    /// A directional light.
+    ///
+    /// # Arguments
+    ///
+    /// * 'direction' - The direction, from which the light falls.
+    /// * 'color' - The color of the light.
+    /// * 'intensity' - The factor applied to the color.
+    pub fn directional(direction: [f32; 3], color: [f32; 3], intensity: f32) -> Self {
+        Self { kind: LightKind::Directional, vector: direction, color, intensity }
+    }
+}
+
+/// The lighting setups, which are available by name.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LightPreset {
+    /// The single light, which the viewer always had. It sits above and to the right of the
+    /// camera.
+    Classic,
+    /// The three-point lighting of a photo studio: A warm key light, a cool fill light from the
+    /// other side and a rim light from behind.
+    Studio,
+    /// A light, which shines from the camera onto the object.
+    Headlight,
+    /// A light from straight above, like in a showroom.
+    Top,
+}
+impl LightPreset {
+    pub const ALL: [LightPreset; 4] = [LightPreset::Classic, LightPreset::Studio, LightPreset::Headlight, LightPreset::Top];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightPreset::Classic => "classic",
+            LightPreset::Studio => "studio",
+            LightPreset::Headlight => "headlight",
+            LightPreset::Top => "top",
+        }
+    }
+
+    /// The next preset in the order of ALL.
+    pub fn next(&self) -> Self {
+        let index = LightPreset::ALL.iter().position(|preset| preset == self).unwrap();
+        LightPreset::ALL[(index + 1) % LightPreset::ALL.len()]
+    }
+
+    /// The lights of the preset.
+    pub fn lighting(&self) -> Lighting {
+        let white = [1.0, 1.0, 1.0];
+        let (lights, ambient, key_on_camera) = match self {
+            LightPreset::Classic => (vec![Light::directional([1.4, 0.4, -0.7], white, 1.0)], 0.33, true),
+            LightPreset::Studio => (vec![
+                Light::directional([-1.0, 1.0, -1.0], [1.0, 0.95, 0.85], 1.0),
+                Light::directional([1.0, 0.3, -0.8], [0.8, 0.85, 1.0], 0.4),
+                Light::directional([0.3, 0.8, 1.0], white, 0.6),
+            ], 0.2, false),
+            LightPreset::Headlight => (vec![Light::directional([0.0, 0.0, -1.0], white, 1.0)], 0.2, true),
+            LightPreset::Top => (vec![Light::directional([0.0, 1.0, 0.0], white, 1.0)], 0.3, false),
+        };
+        Lighting { preset: Some(*self), ambient, key_on_camera, lights }
+    }
+}
+impl FromStr for LightPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LightPreset::ALL.iter().copied().find(|preset| preset.name() == s).ok_or_else(|| {
+            let names: Vec<&str> = LightPreset::ALL.iter().map(LightPreset::name).collect();
+            format!("Unknown light preset '{s}', expected one of {}", names.join(", "))
+        })
+    }
+}
+
+/// The lights of the scene. The first light is the key light, which may be attached to the
+/// camera. Then its vector is given in the coordinates of the camera: x to the right, y up and z
+/// along the line of sight.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lighting {
+    /// The preset, the lights come from, as long as they are not edited.
+    #[serde(skip)]
+    pub preset: Option<LightPreset>,
+    /// The brightness of the light, which comes from everywhere.
+    pub ambient: f32,
+    pub key_on_camera: bool,
+    pub lights: Vec<Light>,
+}
+impl Default for Lighting {
+    fn default() -> Self {
+        LightPreset::Classic.lighting()
+    }
+}
+impl Lighting {
+    /// The lighting of the config file in the config directory of the user. Without a config
+    /// file, this is the classic preset.
+    pub fn load() -> Self {
+        let path = match config_path() {
+            Some(path) if path.exists() => path,
+            _ => return Self::default(),
+        };
+        let lighting = std::fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| toml::from_str::<Lighting>(&text).map_err(|error| error.to_string()));
+        match lighting {
+            Ok(mut lighting) => {
+                log::info!("Lighting loaded from {}", path.display());
+                lighting.lights.truncate(MAX_LIGHTS);
+                lighting
+            }
+            Err(error) => {
+                log::error!("Cannot read the lighting from {}: {error}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// Writes the lighting into the config file, so it is used from now on.
+    pub fn save(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let path = config_path().ok_or("There is no config directory")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, toml::to_string(self)?)?;
+        Ok(path)
+    }
+
+    /// Attaches the key light to the camera or leaves it in the scene. The light keeps the
+    /// direction, from which it currently falls, or its position.
+    ///
+    /// # Arguments
+    ///
+    /// * 'on_camera' - True, if the key light moves with the camera from now on.
+    /// * 'view_direction' - The line of sight of the camera.
+    /// * 'up' - The up direction of the camera.
+    /// * 'view_position' - The position of the camera.
+    pub fn set_key_on_camera(&mut self, on_camera: bool, view_direction: &[f32; 3], up: &[f32; 3],
+                             view_position: &[f32; 3]) {
+        if on_camera != self.key_on_camera {
+            if let Some(key) = self.lights.first_mut() {
+                let (right, camera_up, forward) = camera_axes(view_direction, up);
+                let origin = match key.kind {
+                    LightKind::Directional => Vector3::zeros(),
+                    LightKind::Point => Vector3::from(*view_position),
+                };
+                key.vector = if on_camera {
+                    let vector = Vector3::from(key.vector) - origin;
+                    [vector.dot(&right), vector.dot(&camera_up), vector.dot(&forward)]
+                } else {
+                    (right * key.vector[0] + camera_up * key.vector[1] + forward * key.vector[2] + origin).into()
+                };
+            }
+            self.key_on_camera = on_camera;
+        }
+    }
+
+    /// The values of the lights for the shader of the object, in world coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * 'view_direction' - The line of sight of the camera.
+    /// * 'up' - The up direction of the camera.
+    /// * 'view_position' - The position of the camera.
+    pub fn uniforms(&self, view_direction: &[f32; 3], up: &[f32; 3], view_position: &[f32; 3]) -> LightUniforms {
+        let (right, camera_up, forward) = camera_axes(view_direction, up);
+        let mut uniforms = LightUniforms { ambient: [self.ambient; 3], ..LightUniforms::default() };
+        for (i, light) in self.lights.iter().take(MAX_LIGHTS).enumerate() {
+            let vector = Vector3::from(light.vector);
+            let vector = if i == 0 && self.key_on_camera {
+                let vector = right * vector.x + camera_up * vector.y + forward * vector.z;
+                match light.kind {
+                    LightKind::Directional => vector,
+                    LightKind::Point => vector + Vector3::from(*view_position),
+                }
+            } else {
+                vector
+            };
+            let w = match light.kind { LightKind::Directional => 0.0, LightKind::Point => 1.0 };
+            uniforms.positions[i] = [vector.x, vector.y, vector.z, w];
+            uniforms.colors[i] = light.color.map(|channel| channel * light.intensity);
+            uniforms.count += 1;
+        }
+        uniforms
+    }
+}
+
+/// The lights as they are passed to the shader. The position of a directional light has w = 0
+/// and points towards the light.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LightUniforms {
+    count: i32,
+    positions: [[f32; 4]; MAX_LIGHTS],
+    colors: [[f32; 3]; MAX_LIGHTS],
+    ambient: [f32; 3],
+}
+impl LightUniforms {
+    /// The direction towards the key light, which casts the shadows. A point light is treated, as
+    /// if it were far away in the direction, in which it is seen from the provided point.
+    ///
+    /// # Arguments
+    ///
+    /// * 'center' - The point in world coordinates, which the light shines upon.
+    pub fn key_direction(&self, center: &Vector3<f32>) -> Option<Vector3<f32>> {
+        if self.count == 0 {
+            return None;
+        }
+        let [x, y, z, w] = self.positions[0];
+        let direction = if w == 0.0 { Vector3::new(x, y, z) } else { Vector3::new(x, y, z) - center };
+        direction.try_normalize(f32::EPSILON)
+    }
+}
+
+/// Adds the lights to other uniforms. The uniform macro of glium knows no arrays.
+pub struct WithLights<'a, U: Uniforms> {
+    pub uniforms: U,
+    pub lights: &'a LightUniforms,
+}
+impl<U: Uniforms> Uniforms for WithLights<'_, U> {
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut output: F) {
+        self.uniforms.visit_values(&mut output);
+        output("u_light_count", UniformValue::SignedInt(self.lights.count));
+        output("u_ambient", UniformValue::Vec3(self.lights.ambient));
+        for i in 0..MAX_LIGHTS {
+            output(&format!("u_light_position[{i}]"), UniformValue::Vec4(self.lights.positions[i]));
+            output(&format!("u_light_color[{i}]"), UniformValue::Vec3(self.lights.colors[i]));
+        }
+    }
+}
+
+/// The axes of the camera in world coordinates: To the right, up and along the line of sight.
+fn camera_axes(view_direction: &[f32; 3], up: &[f32; 3]) -> (Vector3<f32>, Vector3<f32>, Vector3<f32>) {
+    let forward = Vector3::from(*view_direction).try_normalize(f32::EPSILON).unwrap_or_else(Vector3::z);
+    let right = navigation::right(&forward, &Vector3::from(*up));
+    (right, forward.cross(&right), forward)
+}
+
+/// The location of the config file with the lighting.
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("glium-obj").join("lighting.toml"))
+}
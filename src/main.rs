@@ -4,42 +4,268 @@ extern crate glium;
 mod model;
 mod view;
 mod hud;
+mod layout;
+mod reference;
+mod bounds;
+mod dimensions;
+mod units;
+mod format;
+mod options;
+mod picking;
+mod highlight;
+mod measure;
+mod annotations;
+mod export;
+mod turntable;
+mod thumbnails;
+mod bindings;
+mod navigation;
+mod camera;
+mod session;
+mod history;
+mod changes;
+mod statistics;
+mod performance;
+mod console;
+mod remote;
+mod lighting;
+mod shadow;
 
+use bindings::{Action, Bindings, KeyChord};
+use camera::StandardView;
+use changes::{Change, Subscription};
+use console::{LightCommand, Setting};
+use export::ExportRequest;
 use model::Model;
+use navigation::Navigation;
+use options::{Command, Options};
+use remote::{RemoteRequest, Response};
+use session::Session;
 use view::View;
 
-use glium::glutin::event::{Event, KeyboardInput};
+// The factor applied to the speed of the movements, while Shift is held.
+const FAST_FACTOR: f32 = 4.0;
+
+// The factor applied to the speed of the movements, while Ctrl is held.
+const SLOW_FACTOR: f32 = 0.25;
+
+// The angle in RAD, by which the field of view changes with each key press.
+const FIELD_OF_VIEW_INCR: f32 = 5.0 * std::f32::consts::PI / 180.0;
+
+// The factor, by which the near or far plane moves with each key press.
+const CLIP_FACTOR: f32 = 2.0;
+
+// The angle in RAD, by which the mouse turns the view for each pixel.
+const LOOK_SPEED: f32 = 0.005;
+
+// The time in seconds, a key must be held, before the continuous movement starts. A shorter tap
+// moves by a single step.
+const HOLD_DELAY: f32 = 0.3;
+
+// The longest time in seconds, which is integrated at once. This avoids jumps after the
+// application was blocked, e.g. by an export.
+const MAX_FRAME_TIME: f32 = 0.1;
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use nalgebra::Vector3;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use glium::glutin::event::{Event, KeyboardInput, ModifiersState, VirtualKeyCode};
 use glium::glutin::event_loop::ControlFlow;
 use glium::{glutin, Display};
 
 /// This application expected one parameter on the command line, which must be the path to a
 /// wavefront obj file with triangulated surfaces. It loads this file and allows to manipulte it
-/// with the QWEASD-+ keys. The view point might be changed with the Numpad-Keys.
+/// with the QWEASD-+ keys. The view point might be changed with the Numpad-Keys. The keys may be
+/// changed in the file bindings.toml in the config directory of the user.
 ///
 /// # Usage
-/// glutin-obj <obj-file-name>
+/// glutin-obj [--model-unit <unit>] [--unit <unit>] [--export-multiple <n>] [--export-without-hud]
+///            [--export-transparent] [--fov <degrees>] [--near <distance>] [--far <distance>]
+///            [--precision <decimals>] [--scale-format <percent|ratio>]
+///            [--font <mono|serif|ttf-file>] [--font-size <pixels>] [--font-color <#rrggbb[aa]>]
+///            [--script <script-file>] [--remote <port>]
+///            [--light-preset <classic|studio|headlight|top>] [--no-shadows] <obj-file-name>
+///
+/// glutin-obj --session <session-file> [<obj-file-name>]
+///
+/// glutin-obj turntable [--frames <n>] [--size <width>x<height>] [--elevation <degrees>]
+///            [--delay <ms>] [--out <dir-or-gif>] [--export-transparent] [--script <script-file>]
+///            [--light-preset <classic|studio|headlight|top>] [--no-shadows] <obj-file-name>
+///
+/// The model unit is the unit of the coordinates in the obj file, the unit is used to present the
+/// dimensions of the object. Both default to meter. The export options control the image, which
+/// is exported with F11. The field of view defaults to 60 degrees. Near and far plane are fitted
+/// to the scene, unless they are given. A session file restores the object, the display settings,
+/// the camera and the bookmarks, which were saved with Ctrl+S. The precision and the scale format
+/// decide, how the values of the HUD are presented, the font options, how they look. The HUD
+/// grows with the scale factor of the window. A script file contains commands of the console,
+/// one per line, which are executed after the object and the session are loaded. The remote
+/// option starts a HTTP server on localhost, which lets other programs control the viewer. They
+/// must send the token, which is printed at the start. The lights are read from the file
+/// lighting.toml in the config directory, unless a preset is given.
+/// The key light casts shadows, unless they are turned off. They are also turned off, when OpenGL
+/// renders without a graphics card.
+///
+/// glutin-obj thumbnails [--size <width>x<height>] [--elevation <degrees>] [--out <dir>]
+///            [--export-transparent] [--light-preset <classic|studio|headlight|top>]
+///            [--no-shadows] <dir>
+///
+/// The turntable renders the object without a window, while it turns around its vertical axis.
+/// The thumbnails are rendered for all obj files in the directory tree. Both are lit like the
+/// viewer.
 ///
 fn main() {
 
     env_logger::init();
 
-    let obj_file_name = std::env::args().nth(1).expect("No object file provied!");
+    let options = Options::parse(std::env::args().skip(1)).unwrap_or_else(|message| {
+        eprintln!("{message}");
+        std::process::exit(2);
+    });
 
-    let event_loop = glutin::event_loop::EventLoop::new();
+    let lighting = options.light_preset.map_or_else(lighting::Lighting::load, |preset| preset.lighting());
+    if let Command::Thumbnails(settings) = &options.command {
+        let dir = std::path::Path::new(options.input.as_deref().unwrap_or_default());
+        if let Err(error) = thumbnails::render_thumbnails(dir, settings, &lighting, options.shadows,
+                                                          options.export.transparent) {
+            eprintln!("Cannot render the thumbnails: {error}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let session = options.session.as_ref().map(|path| Session::read(path).unwrap_or_else(|error| {
+        eprintln!("Cannot read the session {}: {error}", path.display());
+        std::process::exit(1);
+    }));
+    // An obj file on the command line wins over the file of the session.
+    let input = match (&options.input, &session) {
+        (Some(input), _) => input.clone(),
+        (None, Some(session)) => session.file.to_string_lossy().into_owned(),
+        (None, None) => unreachable!("The options make sure, that there is an input"),
+    };
+
+    let mut model = Model::new(&input).expect("Cannot read object file!");
+    // The session is restored first, so the options, which are given, win over it.
+    if let Some(session) = &session {
+        session.apply(&mut model);
+    }
+    if let Some(model_unit) = options.model_unit {
+        model.model_unit = model_unit;
+    }
+    if let Some(unit) = options.unit {
+        model.unit = unit;
+    }
+    if let Err(error) = options.lens.apply(&mut model.lens) {
+        eprintln!("{error}");
+        std::process::exit(2);
+    }
+    model.export = options.export;
+    model.hud_format = options.hud_format;
+    model.hud_style = options.hud_style.clone();
+    model.lighting = lighting;
+    model.show_shadows = options.shadows;
+    model.session_file = options.session.clone();
+    if let Some(script) = &options.script {
+        if let Err(error) = run_script(script, &mut model) {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    }
+
+    if let Command::Turntable(settings) = &options.command {
+        if let Err(error) = turntable::render_turntable(&mut model, settings, options.export.transparent) {
+            eprintln!("Cannot render the turntable: {error}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let event_loop = glutin::event_loop::EventLoopBuilder::<RemoteRequest>::with_user_event().build();
     let wb = glutin::window::WindowBuilder::new()
         .with_title("Obj viewer based on obj-rs");
     let cb = glutin::ContextBuilder::new()
         .with_depth_buffer(24);
     let display = glium::Display::new(wb, cb, &event_loop).unwrap();
+    model.show_shadows = shadow::shadows_on(&display, model.show_shadows);
+
+    if let Some(port) = options.remote {
+        let proxy = event_loop.create_proxy();
+        match remote::start(port, move |request| proxy.send_event(request).is_ok()) {
+            Ok(server) => println!("Control server on http://127.0.0.1:{} with the token {}", server.port, server.token),
+            Err(error) => {
+                eprintln!("Cannot start the control server on port {port}: {error}");
+                std::process::exit(1);
+            },
+        }
+    }
 
-    let mut model = Model::new(&obj_file_name).expect("Cannot read object file!");
     let mut view = View::new(&display, &model);
+    let mut controller = Controller::new(Bindings::load());
 
     event_loop.run(move |event, _, control_flow| {
-        run(&display, &mut model, &mut view, &event, control_flow);
+        run(&display, &mut model, &mut view, &mut controller, &event, control_flow);
     });
 }
 
+/// A key of a continuous action, which is held.
+struct HeldKey {
+    action: Action,
+    /// The time, when the key was pressed.
+    since: Instant,
+}
+
+/// The state of the input handling, which is not part of the model.
+struct Controller {
+    /// The table, which maps the keys to the actions.
+    bindings: Bindings,
+    /// The modifier keys, which are currently held.
+    modifiers: ModifiersState,
+    /// The keys of continuous actions, which are currently held, by their scan code. The action
+    /// is resolved, when the key is pressed, so changing the modifiers doesn't change it.
+    held: HashMap<u32, HeldKey>,
+    /// The time of the last frame.
+    last_frame: Instant,
+    /// True, while the right mouse button is held to look around or to orbit.
+    looking: bool,
+    /// The last position of the cursor in pixels.
+    cursor: Option<(f64, f64)>,
+    /// The triangle under the cursor changes, when the camera or the object moves.
+    picking: Subscription,
+    /// True after the console was opened by a key, until the next key is pressed. The character
+    /// of the key, which opened the console, must not be typed into it.
+    skip_character: bool,
+}
+impl Controller {
+    fn new(bindings: Bindings) -> Self {
+        Self {
+            bindings,
+            modifiers: ModifiersState::empty(),
+            held: HashMap::new(),
+            last_frame: Instant::now(),
+            looking: false,
+            cursor: None,
+            picking: Subscription::new(&[Change::Camera, Change::Transform, Change::Geometry]),
+            skip_character: false,
+        }
+    }
+
+    /// The factor for the speed of the movements, which depends on the held modifiers.
+    fn speed_factor(&self) -> f32 {
+        if self.modifiers.shift() {
+            FAST_FACTOR
+        } else if self.modifiers.ctrl() {
+            SLOW_FACTOR
+        } else {
+            1.0
+        }
+    }
+}
+
 /// This is the central controller of the application. It receives all user input, distributes this
 /// to the model and controls the update of the view.
 ///
@@ -48,26 +274,79 @@ fn main() {
 /// * 'display' - The object, where we should render upon.
 /// * 'model' - The model of the application.
 /// * 'view' - The presentation of the model.
+/// * 'controller' - The key bindings and the state of the keyboard.
 /// * 'event' - The event, wich has to be processed now.
 /// * 'control_flow' - A glutin specific object, which is basically used to end the application.
-fn run<T>(display: &Display,
-          model: &mut Model,
-          view: &mut View,
-          event: &Event<T>,
+fn run(display: &Display,
+       model: &mut Model,
+       view: &mut View,
+       controller: &mut Controller,
+       event: &Event<RemoteRequest>,
           control_flow: &mut ControlFlow)
 {
     let next_frame_time = std::time::Instant::now() +
         std::time::Duration::from_nanos(100_000_000 / 30);
-    *control_flow = glutin::event_loop::ControlFlow::WaitUntil(next_frame_time);
+    *control_flow = if model.render_continuously {
+        glutin::event_loop::ControlFlow::Poll
+    } else {
+        glutin::event_loop::ControlFlow::WaitUntil(next_frame_time)
+    };
 
-    handle_event(event, model, control_flow);
+    if let Event::UserEvent(request) = event {
+        request.reply(serve(request, display, model, view));
+    }
+    handle_event(event, display, model, controller, control_flow);
+
+    // A frame is drawn once per iteration of the event loop, after all of its events are handled.
+    // Drawing after each event would count several frames per iteration and let the mouse change
+    // the frame timing.
+    if let Event::MainEventsCleared = event {
+        frame(display, model, view, controller);
+    }
+}
+
+/// Advances the continuous movements and the animations by the time since the last frame and
+/// draws the frame.
+///
+/// # Arguments
+///
+/// * 'display' - The object, where we should render upon.
+/// * 'model' - The model of the application.
+/// * 'view' - The presentation of the model.
+/// * 'controller' - The key bindings and the state of the keyboard.
+fn frame(display: &Display, model: &mut Model, view: &mut View, controller: &mut Controller)
+{
+    // The continuous movements depend on the time since the last frame, not on the key repeat.
+    let now = Instant::now();
+    let seconds = now.duration_since(controller.last_frame).as_secs_f32().min(MAX_FRAME_TIME);
+    controller.last_frame = now;
+    // The tap already moved by a step, the continuous movement starts after the delay.
+    let held: Vec<(Action, f32)> = controller.held.values()
+        .map(|key| (key.action, now.duration_since(key.since).as_secs_f32() - HOLD_DELAY))
+        .collect();
+    for (action, moving) in held {
+        if moving > 0.0 {
+            perform_continuously(action, model, seconds.min(moving) * controller.speed_factor());
+        }
+    }
+    model.animate(seconds);
+
+    if controller.picking.take(model.changes()) {
+        if let Some((x, y)) = controller.cursor {
+            let (width, height) = display.get_framebuffer_dimensions();
+            model.set_hover(picking::pick_at(model, x as f32, y as f32, width, height));
+        }
+    }
 
     // The drawing part
-    view.draw(display, model);
+    view.draw(display, model, &controller.bindings);
 
-    // In cases, that there is more than one view, we should reset the changes here. For more
-    // complex systems, we have to establish an observer pattern.
-    model.reset_changed();
+    if let Some(request) = model.take_export_request() {
+        match export::export_image(display, view, model, request) {
+            Ok(path) => log::info!("Image written to {}", path.display()),
+            Err(error) => log::error!("Cannot write the image: {error}"),
+        }
+    }
 }
 
 /// Handles the events of the window manager and the devices, including keyboards.
@@ -77,24 +356,79 @@ fn run<T>(display: &Display,
 /// # Arguments
 ///
 /// * 'event' - The device or windows event to be handled.
+/// * 'display' - The display, which provides the size of the window for picking.
 /// * 'model' - The model, which can be modified by the events.
+/// * 'controller' - The key bindings and the state of the keyboard.
 /// * 'control_flow' - A glutin specific object, which is basically used to end the application.
-fn handle_event<T>(event: &Event<T>, model: &mut Model, control_flow: &mut ControlFlow)
+fn handle_event<T>(event: &Event<T>,
+                   display: &Display,
+                   model: &mut Model,
+                   controller: &mut Controller,
+                   control_flow: &mut ControlFlow)
 {
-    use glutin::event::WindowEvent;
+    use glutin::event::{ElementState, MouseButton, WindowEvent};
     match event {
         Event::WindowEvent { event, .. } => match event {
             glutin::event::WindowEvent::CloseRequested => {
                 *control_flow = glutin::event_loop::ControlFlow::Exit;
                 return;
             },
-            glutin::event::WindowEvent::Resized(_) => {
-                model.changed();    // Ugly but, I think Ok in this unique case.
+            glutin::event::WindowEvent::Resized(_) | glutin::event::WindowEvent::ScaleFactorChanged { .. } => {
+                model.notify(Change::Display);    // Ugly but, I think Ok in this unique case.
                 return;
             }
             WindowEvent::KeyboardInput { input, .. } => {
                 log::debug!("KeyboardInput: {:?}", input);
-                handle_keyboard_event(control_flow, model, input);
+                handle_keyboard_event(control_flow, model, controller, input);
+                return;
+            },
+            WindowEvent::ReceivedCharacter(character) => {
+                if model.console.open && !controller.skip_character && !character.is_control() {
+                    model.console.type_character(*character);
+                    model.notify(Change::Display);
+                }
+                controller.skip_character = false;
+                return;
+            },
+            WindowEvent::ModifiersChanged(modifiers) => {
+                controller.modifiers = *modifiers;
+                return;
+            },
+            WindowEvent::Focused(false) => {
+                // The release of the keys is not reported to a window without focus.
+                controller.held.clear();
+                controller.looking = false;
+                model.end_step();
+                return;
+            },
+            WindowEvent::CursorMoved { position, .. } => {
+                if let (true, Some((x, y))) = (controller.looking, controller.cursor) {
+                    let yaw = (position.x - x) as f32 * LOOK_SPEED;
+                    let pitch = (y - position.y) as f32 * LOOK_SPEED;
+                    match model.navigation {
+                        Navigation::Fly => model.look(yaw, pitch),
+                        Navigation::Orbit => model.orbit(-yaw, -pitch),
+                    }
+                }
+                controller.cursor = Some((position.x, position.y));
+                let (width, height) = display.get_framebuffer_dimensions();
+                let pick = picking::pick_at(model, position.x as f32, position.y as f32, width, height);
+                model.set_hover(pick);
+                return;
+            },
+            WindowEvent::CursorLeft { .. } => {
+                controller.cursor = None;
+                model.set_hover(None);
+                return;
+            },
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } => {
+                model.select();
+                return;
+            },
+            WindowEvent::MouseInput { state, button: MouseButton::Right, .. } => {
+                // Dragging with the mouse is a gesture of its own in the history.
+                controller.looking = *state == ElementState::Pressed;
+                model.end_step();
                 return;
             },
             _ => {
@@ -111,17 +445,23 @@ fn handle_event<T>(event: &Event<T>, model: &mut Model, control_flow: &mut Contr
     }
 }
 
-/// Handles only the keyboard events send to the application window.
+/// Handles only the keyboard events send to the application window. The key is translated into
+/// an action with the key bindings of the controller. The press of a continuous action moves by a
+/// single step. If the key is held longer, the action is performed in each frame, until the key
+/// is released.
 ///
 /// # Arguments
 ///
 /// * 'input' - The KeyboardInput structure of glutin. This provides access to the scan code and
 /// the key code of the event.
 /// * 'model' - The model, which can be modified by the events.
+/// * 'controller' - The key bindings and the state of the keyboard.
 /// * 'control_flow' - A glutin specific object, which is basically used to end the application.
-fn handle_keyboard_event(control_flow: &mut ControlFlow, model: &mut Model, input: &KeyboardInput)
+fn handle_keyboard_event(control_flow: &mut ControlFlow,
+                         model: &mut Model,
+                         controller: &mut Controller,
+                         input: &KeyboardInput)
 {
-    use glutin::event::VirtualKeyCode;
     use glutin::event::ElementState;
 
     let key_code = if let Some(key_code) = input.virtual_keycode {
@@ -131,46 +471,426 @@ fn handle_keyboard_event(control_flow: &mut ControlFlow, model: &mut Model, inpu
         return;
     };
 
-    match input.state {
-        ElementState::Pressed =>
-            match key_code {
-                VirtualKeyCode::Key2 => model.view_position_down(),
-                VirtualKeyCode::Numpad2 => model.view_position_down(),
-                VirtualKeyCode::Key5 => model.reset_view(),
-                VirtualKeyCode::Numpad5 => model.reset_view(),
-                VirtualKeyCode::Key8 => model.view_position_up(),
-                VirtualKeyCode::Numpad8 => model.view_position_up(),
-                VirtualKeyCode::Key4 => model.view_position_left(),
-                VirtualKeyCode::Numpad4 => model.view_position_left(),
-                VirtualKeyCode::Key6 => model.view_position_right(),
-                VirtualKeyCode::Numpad6 => model.view_position_right(),
-                VirtualKeyCode::Key9 => model.view_position_forward(),
-                VirtualKeyCode::Numpad9 => model.view_position_forward(),
-                VirtualKeyCode::Key3 => model.view_position_backward(),
-                VirtualKeyCode::Numpad3 => model.view_position_backward(),
-                VirtualKeyCode::A => model.rotate_left(),
-                VirtualKeyCode::D => model.rotate_right(),
-                VirtualKeyCode::W => model.rotate_up(),
-                VirtualKeyCode::S => model.rotate_down(),
-                VirtualKeyCode::Q => model.roll_up(),
-                VirtualKeyCode::E => model.roll_down(),
-                VirtualKeyCode::Minus => model.scale_down(),
-                VirtualKeyCode::Plus => model.scale_up(),
-                VirtualKeyCode::Equals => model.scale_up(), // Needed because of my Mac-Windows-KB-Mappings
-                VirtualKeyCode::Down => model.move_y_neg(),
-                VirtualKeyCode::Up => model.move_y_pos(),
-                VirtualKeyCode::PageUp => model.move_z_neg(),
-                VirtualKeyCode::PageDown => model.move_z_pos(),
-                VirtualKeyCode::Left => model.move_x_neg(),
-                VirtualKeyCode::Right => model.move_x_pos(),
-                _ => {}
+    let chord = KeyChord::new(key_code, controller.modifiers);
+    if input.state == ElementState::Pressed {
+        controller.skip_character = false;
+    }
+    // The release stops the movement of the key, whatever the modifiers are now. It ends the step
+    // in the history, pressing the key again starts a new one.
+    if input.state == ElementState::Released && controller.held.remove(&input.scancode).is_some() {
+        model.end_step();
+    }
+    if model.console.open {
+        handle_console_key(model, controller, key_code, &chord, input.state);
+        return;
+    }
+    let action = if let Some(action) = controller.bindings.action(&chord, model.navigation) {
+        action
+    } else {
+        return;
+    };
+
+    // The application ends, when the key is released. Otherwise the release would reach the
+    // window, which is below ours.
+    match (input.state, action) {
+        (ElementState::Released, Action::Quit) => *control_flow = glutin::event_loop::ControlFlow::Exit,
+        (ElementState::Released, _) => (),
+        (ElementState::Pressed, action) if action.is_continuous() => {
+            // The key repeat of the system sends further presses of a held key.
+            let factor = controller.speed_factor();
+            if let Entry::Vacant(entry) = controller.held.entry(input.scancode) {
+                entry.insert(HeldKey { action, since: Instant::now() });
+                model.end_step();
+                perform_continuously(action, model, step_time(action) * factor);
             }
+        },
+        (ElementState::Pressed, action) => {
+            // The held keys may mean something else in the other navigation mode. The keys, which
+            // are typed into the console, must not move anything.
+            if action == Action::ToggleNavigation || action == Action::ToggleConsole {
+                controller.held.clear();
+            }
+            controller.skip_character = action == Action::ToggleConsole;
+            perform(action, model);
+        },
+    }
+}
 
-        ElementState::Released =>
-            match key_code {
-                VirtualKeyCode::Escape => *control_flow = glutin::event_loop::ControlFlow::Exit,
-                _ => {},
+/// Handles the keys, while the console is open. The typed characters arrive separately, see
+/// handle_event(). The console is closed by its key or by Escape. Escape closes it, when the key is
+/// released, so the release doesn't quit the application.
+///
+/// # Arguments
+///
+/// * 'model' - The model with the console.
+/// * 'controller' - The key bindings and the state of the keyboard.
+/// * 'key_code' - The key, which was pressed or released.
+/// * 'chord' - The key together with the held modifiers.
+/// * 'state' - Whether the key was pressed or released.
+fn handle_console_key(model: &mut Model,
+                      controller: &Controller,
+                      key_code: VirtualKeyCode,
+                      chord: &KeyChord,
+                      state: glutin::event::ElementState)
+{
+    use glutin::event::ElementState;
+
+    match (state, key_code) {
+        (ElementState::Released, VirtualKeyCode::Escape) => model.toggle_console(),
+        (ElementState::Released, _) => return,
+        (_, VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter) => {
+            if let Some(line) = model.console.submit() {
+                match console::Command::parse(&line).and_then(|command| execute(command, model)) {
+                    Ok(output) => model.console.print(&output),
+                    Err(error) => model.console.print(&format!("Error: {error}")),
+                }
+            }
+        },
+        (_, VirtualKeyCode::Back) => model.console.backspace(),
+        (_, VirtualKeyCode::Up) => model.console.history_back(),
+        (_, VirtualKeyCode::Down) => model.console.history_forward(),
+        (_, VirtualKeyCode::Tab) => model.console.complete(),
+        _ if controller.bindings.action(chord, model.navigation) == Some(Action::ToggleConsole) => {
+            model.toggle_console();
+        },
+        _ => return,
+    }
+    model.notify(Change::Display);
+}
+
+/// Executes a command of the console or of a script.
+///
+/// # Arguments
+///
+/// * 'command' - The command to execute.
+/// * 'model' - The model, which is modified by the command.
+///
+/// Returns the text, which is printed as result of the command, or the reason, why it failed.
+fn execute(command: console::Command, model: &mut Model) -> Result<String, String>
+{
+    // Each command is a step of its own in the history.
+    model.end_step();
+    match command {
+        console::Command::Rotate(angle, axis) => model.rotate_around(axis, angle),
+        console::Command::Scale(factor) => model.set_scaling_factor(factor),
+        console::Command::Move(offset) => model.move_object(offset.into()),
+        console::Command::Load(path) => {
+            model.load(&path.to_string_lossy())
+                .map_err(|error| format!("Cannot load {}: {error}", path.display()))?;
+            return Ok(format!("Loaded {}", path.display()));
+        },
+        console::Command::Set(setting) => match setting {
+            Setting::Light(direction) => model.set_light(direction),
+            Setting::FieldOfView(angle) => model.set_field_of_view(angle),
+            Setting::Unit(unit) => model.set_unit(unit),
+            Setting::Projection(projection) => model.set_projection(projection),
+            Setting::Navigation(navigation) => model.set_navigation(navigation),
+            Setting::Precision(precision) => model.set_hud_format(format::HudFormat { precision, ..model.hud_format }),
+            Setting::ScaleFormat(scale_style) => model.set_hud_format(format::HudFormat { scale_style, ..model.hud_format }),
+        },
+        console::Command::View(view) => model.show_standard_view(view),
+        console::Command::Camera(position, target) => model.place_camera(position.into(), target.map(Vector3::from)),
+        console::Command::Light(command) => return execute_light(command, model),
+        console::Command::Perform(action) => perform(action, model),
+        console::Command::Help => return Ok(console::help().collect::<Vec<_>>().join("\n")),
+    }
+    Ok(String::new())
+}
+
+/// Changes the lights of the model.
+///
+/// # Arguments
+///
+/// * 'command' - The change of the lights.
+/// * 'model' - The model with the lights.
+///
+/// Returns the list of the lights, when it is asked for, or the reason, why the change failed.
+fn execute_light(command: LightCommand, model: &mut Model) -> Result<String, String>
+{
+    // The light with a number, starting at 1.
+    fn light(lighting: &mut lighting::Lighting, number: usize) -> Result<&mut lighting::Light, String> {
+        let count = lighting.lights.len();
+        lighting.lights.get_mut(number - 1).ok_or_else(|| format!("There is no light {number}, there are {count}"))
+    }
+
+    match command {
+        LightCommand::List => {
+            let lighting = &model.lighting;
+            let mut lines = vec![format!("Preset {}, ambient {}, key light on the camera: {}",
+                                         lighting.preset.map_or("none", |preset| preset.name()),
+                                         lighting.ambient, lighting.key_on_camera)];
+            for (i, light) in lighting.lights.iter().enumerate() {
+                lines.push(format!("{}: {:?} ({}, {}, {}), color ({}, {}, {}), intensity {}", i + 1, light.kind,
+                                   light.vector[0], light.vector[1], light.vector[2],
+                                   light.color[0], light.color[1], light.color[2], light.intensity));
+            }
+            return Ok(lines.join("\n"));
+        },
+        LightCommand::Preset(preset) => model.set_lighting(preset.lighting()),
+        LightCommand::Camera(on_camera) => model.set_key_light_on_camera(on_camera),
+        LightCommand::Ambient(ambient) => model.edit_lighting(|lighting| {
+            lighting.ambient = ambient;
+            Ok(())
+        })?,
+        LightCommand::Add => model.edit_lighting(|lighting| {
+            if lighting.lights.len() == lighting::MAX_LIGHTS {
+                return Err(format!("There are already {} lights", lighting::MAX_LIGHTS));
+            }
+            lighting.lights.push(lighting::Light::directional([0.0, 1.0, 0.0], [1.0, 1.0, 1.0], 0.5));
+            Ok(())
+        })?,
+        LightCommand::Remove(number) => model.edit_lighting(|lighting| {
+            light(lighting, number)?;
+            lighting.lights.remove(number - 1);
+            Ok(())
+        })?,
+        LightCommand::Save => {
+            let path = model.lighting.save().map_err(|error| format!("Cannot save the lights: {error}"))?;
+            return Ok(format!("Lights saved in {}", path.display()));
+        },
+        LightCommand::Direction(number, direction) => model.edit_lighting(|lighting| {
+            let light = light(lighting, number)?;
+            light.kind = lighting::LightKind::Directional;
+            light.vector = direction;
+            Ok(())
+        })?,
+        LightCommand::Position(number, position) => model.edit_lighting(|lighting| {
+            let light = light(lighting, number)?;
+            light.kind = lighting::LightKind::Point;
+            light.vector = position;
+            Ok(())
+        })?,
+        LightCommand::Color(number, color) => model.edit_lighting(|lighting| {
+            light(lighting, number)?.color = color;
+            Ok(())
+        })?,
+        LightCommand::Intensity(number, intensity) => model.edit_lighting(|lighting| {
+            light(lighting, number)?.intensity = intensity;
+            Ok(())
+        })?,
+    }
+    Ok(String::new())
+}
+
+/// Executes the commands of a script file, see execute_script().
+///
+/// # Arguments
+///
+/// * 'path' - The script file.
+/// * 'model' - The model, which is modified by the commands.
+fn run_script(path: &Path, model: &mut Model) -> Result<(), String>
+{
+    let script = std::fs::read_to_string(path)
+        .map_err(|error| format!("Cannot read the script {}: {error}", path.display()))?;
+    let output = execute_script(&script, model)
+        .map_err(|(line, error)| format!("{}:{line}: {error}", path.display()))?;
+    for line in output {
+        log::info!("{line}");
+    }
+    Ok(())
+}
+
+/// Executes commands, one per line. Empty lines and lines starting with '#' are skipped. The
+/// execution stops at the first command, which fails.
+///
+/// # Arguments
+///
+/// * 'script' - The commands.
+/// * 'model' - The model, which is modified by the commands.
+///
+/// Returns the output of the commands, or the number of the failed line together with the reason.
+fn execute_script(script: &str, model: &mut Model) -> Result<Vec<String>, (usize, String)>
+{
+    let mut output = Vec::new();
+    for (number, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let result = console::Command::parse(line)
+            .and_then(|command| execute(command, model))
+            .map_err(|error| (number + 1, error))?;
+        output.extend(result.lines().map(str::to_string));
+    }
+    Ok(output)
+}
+
+/// Answers a request of the control server. Commands are executed like a script, the other
+/// requests read the state of the viewer or export images.
+///
+/// # Arguments
+///
+/// * 'request' - The request of a client.
+/// * 'display' - The display, which renders the exported images.
+/// * 'model' - The model, which is read or modified by the request.
+/// * 'view' - The view, which renders the exported images.
+fn serve(request: &RemoteRequest, display: &Display, model: &mut Model, view: &mut View) -> Response
+{
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/command") => match execute_script(&request.body, model) {
+            Ok(output) => Response::ok(&remote::Output { output }),
+            Err((line, error)) => Response::error(400, &format!("Line {line}: {error}")),
+        },
+        ("GET", "/state") => Response::ok(&remote::State::of(model)),
+        ("GET", "/pick") => match (request.parameter("x"), request.parameter("y")) {
+            (None, None) => Response::ok(&remote::Picked::of(model.current_pick())),
+            (Some(x), Some(y)) => match (x.parse::<f32>(), y.parse::<f32>()) {
+                (Ok(x), Ok(y)) => {
+                    let (width, height) = display.get_framebuffer_dimensions();
+                    let pick = picking::pick_at(model, x, y, width, height);
+                    Response::ok(&remote::Picked::of(pick.as_ref()))
+                },
+                _ => Response::error(400, "The pixel needs numbers as x and y"),
+            },
+            _ => Response::error(400, "The pixel needs both x and y"),
+        },
+        ("POST", "/screenshot") | ("POST", "/export") => {
+            let request = if request.path == "/export" { ExportRequest::Image } else { ExportRequest::Screenshot };
+            match export::export_image(display, view, model, request) {
+                Ok(path) => Response::ok(&remote::Written { path: path.to_string_lossy().into_owned() }),
+                Err(error) => Response::error(500, &format!("Cannot write the image: {error}")),
+            }
+        },
+        (method, path) => Response::error(404, &format!("Unknown request {method} {path}")),
+    }
+}
+
+/// Performs an action, which is triggered by the user.
+///
+/// # Arguments
+///
+/// * 'action' - The action to perform.
+/// * 'model' - The model, which is modified by the action.
+fn perform(action: Action, model: &mut Model)
+{
+    match action {
+        Action::ResetView => model.reset_view(),
+        Action::ResetObject => model.reset_object(),
+        Action::Undo => model.undo(),
+        Action::Redo => model.redo(),
+        Action::ToggleNavigation => {
+            model.toggle_navigation();
+            log::info!("Navigation: {:?}", model.navigation);
+        },
+        Action::ToggleProjection => model.toggle_projection(),
+        Action::FrontView => model.show_standard_view(StandardView::Front),
+        Action::BackView => model.show_standard_view(StandardView::Back),
+        Action::LeftView => model.show_standard_view(StandardView::Left),
+        Action::RightView => model.show_standard_view(StandardView::Right),
+        Action::TopView => model.show_standard_view(StandardView::Top),
+        Action::BottomView => model.show_standard_view(StandardView::Bottom),
+        Action::IsometricView => model.show_standard_view(StandardView::Isometric),
+        Action::WidenFieldOfView => model.widen_field_of_view(FIELD_OF_VIEW_INCR),
+        Action::NarrowFieldOfView => model.widen_field_of_view(-FIELD_OF_VIEW_INCR),
+        Action::NearCloser => model.scale_near(1.0 / CLIP_FACTOR),
+        Action::NearFarther => model.scale_near(CLIP_FACTOR),
+        Action::FarCloser => model.scale_far(1.0 / CLIP_FACTOR),
+        Action::FarFarther => model.scale_far(CLIP_FACTOR),
+        Action::ToggleAutoClip => model.toggle_auto_clip(),
+        Action::RecallBookmark(number) => model.recall_bookmark(number),
+        Action::StoreBookmark(number) => model.store_bookmark(number),
+        Action::SaveSession => {
+            let path = model.session_file.clone()
+                .unwrap_or_else(|| PathBuf::from(format!("session-{}.toml", export::timestamp())));
+            match Session::of(model).write(&path) {
+                Ok(()) => {
+                    log::info!("Session written to {}", path.display());
+                    model.session_file = Some(path);
+                },
+                Err(error) => log::error!("Cannot write the session: {error}"),
             }
+        },
+        Action::ToggleGrid => model.toggle_grid(),
+        Action::ToggleAxes => model.toggle_axes(),
+        Action::ToggleGizmo => model.toggle_gizmo(),
+        Action::ToggleBounds => model.toggle_bounds(),
+        Action::ToggleOrientedBounds => model.toggle_oriented_bounds(),
+        Action::ToggleShadows => model.toggle_shadows(),
+        Action::ToggleSoftShadows => model.toggle_soft_shadows(),
+        Action::ToggleGround => model.toggle_ground(),
+        Action::ToggleHelp => model.toggle_help(),
+        Action::ToggleObjectSection => model.toggle_object_section(),
+        Action::ToggleCameraSection => model.toggle_camera_section(),
+        Action::ToggleDirectionSection => model.toggle_direction_section(),
+        Action::ToggleStatisticsSection => model.toggle_statistics_section(),
+        Action::TogglePerformanceSection => model.toggle_performance_section(),
+        Action::ToggleContinuousRendering => model.toggle_continuous_rendering(),
+        Action::ToggleConsole => model.toggle_console(),
+        Action::NextLightPreset => model.next_light_preset(),
+        Action::ToggleCameraLight => model.toggle_key_light_on_camera(),
+        Action::NextUnit => model.next_unit(),
+        Action::NextMeasureMode => model.next_measure_mode(),
+        Action::RemoveMeasurement => model.remove_last_measurement(),
+        Action::ExportMeasurements => match export::write_measurements(model) {
+            Ok(path) => log::info!("Measurements written to {}", path.display()),
+            Err(error) => log::error!("Cannot write the measurements: {error}"),
+        },
+        Action::ExportImage => model.request_export(ExportRequest::Image),
+        Action::Screenshot => model.request_export(ExportRequest::Screenshot),
+        Action::Quit => (),
+        // The continuous actions are performed in each frame by perform_continuously().
+        _ => (),
     }
 }
 
+/// The time, in which a continuous action moves as far as a single tap of its key does.
+///
+/// # Arguments
+///
+/// * 'action' - A continuous action.
+fn step_time(action: Action) -> f32
+{
+    use model::{MOVE_SPEED, MOVE_STEP, ROTATION_SPEED, ROTATION_STEP, SCALE_SPEED, SCALE_STEP, VERTICAL_SPEED,
+                VERTICAL_STEP, VIEW_SPEED, VIEW_STEP};
+    match action {
+        Action::RotateLeft | Action::RotateRight | Action::RotateUp | Action::RotateDown |
+        Action::RollUp | Action::RollDown => ROTATION_STEP / ROTATION_SPEED,
+        Action::ScaleUp | Action::ScaleDown => SCALE_STEP.ln() / SCALE_SPEED.ln(),
+        Action::MoveXNeg | Action::MoveXPos | Action::MoveYNeg | Action::MoveYPos |
+        Action::MoveZNeg | Action::MoveZPos => MOVE_STEP / MOVE_SPEED,
+        Action::ViewUp | Action::ViewDown | Action::FlyUp | Action::FlyDown => VERTICAL_STEP / VERTICAL_SPEED,
+        _ => VIEW_STEP / VIEW_SPEED,
+    }
+}
+
+/// Performs a continuous action for a part of a second. The movements happen with the speeds
+/// defined in the model.
+///
+/// # Arguments
+///
+/// * 'action' - The continuous action to perform.
+/// * 'model' - The model, which is modified by the action.
+/// * 'seconds' - The time, the action lasts, already multiplied with the speed factor.
+fn perform_continuously(action: Action, model: &mut Model, seconds: f32)
+{
+    use model::{MOVE_SPEED, ROTATION_SPEED, SCALE_SPEED, VERTICAL_SPEED, VIEW_SPEED};
+    match action {
+        Action::RotateLeft => model.rotate_left(ROTATION_SPEED * seconds),
+        Action::RotateRight => model.rotate_right(ROTATION_SPEED * seconds),
+        Action::RotateUp => model.rotate_up(ROTATION_SPEED * seconds),
+        Action::RotateDown => model.rotate_down(ROTATION_SPEED * seconds),
+        Action::RollUp => model.roll_up(ROTATION_SPEED * seconds),
+        Action::RollDown => model.roll_down(ROTATION_SPEED * seconds),
+        Action::ScaleUp => model.scale_up(SCALE_SPEED.powf(seconds)),
+        Action::ScaleDown => model.scale_down(SCALE_SPEED.powf(seconds)),
+        Action::MoveXNeg => model.move_x_neg(MOVE_SPEED * seconds),
+        Action::MoveXPos => model.move_x_pos(MOVE_SPEED * seconds),
+        Action::MoveYNeg => model.move_y_neg(MOVE_SPEED * seconds),
+        Action::MoveYPos => model.move_y_pos(MOVE_SPEED * seconds),
+        Action::MoveZNeg => model.move_z_neg(MOVE_SPEED * seconds),
+        Action::MoveZPos => model.move_z_pos(MOVE_SPEED * seconds),
+        Action::ViewUp => model.view_position_up(VERTICAL_SPEED * seconds),
+        Action::ViewDown => model.view_position_down(VERTICAL_SPEED * seconds),
+        Action::ViewLeft => model.view_position_left(VIEW_SPEED * seconds),
+        Action::ViewRight => model.view_position_right(VIEW_SPEED * seconds),
+        Action::ViewForward => model.view_position_forward(VIEW_SPEED * seconds),
+        Action::ViewBackward => model.view_position_backward(VIEW_SPEED * seconds),
+        Action::FlyForward => model.fly(VIEW_SPEED * seconds, 0.0, 0.0),
+        Action::FlyBackward => model.fly(-VIEW_SPEED * seconds, 0.0, 0.0),
+        Action::FlyLeft => model.fly(0.0, -VIEW_SPEED * seconds, 0.0),
+        Action::FlyRight => model.fly(0.0, VIEW_SPEED * seconds, 0.0),
+        Action::FlyUp => model.fly(0.0, 0.0, VERTICAL_SPEED * seconds),
+        Action::FlyDown => model.fly(0.0, 0.0, -VERTICAL_SPEED * seconds),
+        _ => (),
+    }
+}
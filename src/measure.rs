@@ -0,0 +1,104 @@
+use nalgebra::{Point3, Vector3};
+
+use crate::model::Model;
+use crate::view::world_matrix;
+
+/// The measurement mode decides, how many surface points are collected for one measurement.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MeasureMode {
+    Off,
+    Distance,
+    Angle,
+}
+impl MeasureMode {
+    /// The next mode in the cycle off -> distance -> angle -> off.
+    pub fn next(&self) -> Self {
+        match self {
+            MeasureMode::Off => MeasureMode::Distance,
+            MeasureMode::Distance => MeasureMode::Angle,
+            MeasureMode::Angle => MeasureMode::Off,
+        }
+    }
+
+    /// The number of points needed for one measurement.
+    pub fn points(&self) -> usize {
+        match self {
+            MeasureMode::Off => 0,
+            MeasureMode::Distance => 2,
+            MeasureMode::Angle => 3,
+        }
+    }
+}
+
+/// A measurement on the surface of the object. The points are stored in object coordinates, so the
+/// measurement sticks to the object, when it is moved, rotated or scaled.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Measurement {
+    /// The distance between two points.
+    Distance([Vector3<f32>; 2]),
+    /// The angle at the second point, between the lines to the first and the third point.
+    Angle([Vector3<f32>; 3]),
+}
+impl Measurement {
+    /// Creates the measurement from the collected points, if there are enough of them.
+    pub fn from_points(points: &[Vector3<f32>]) -> Option<Self> {
+        match points {
+            [a, b] => Some(Measurement::Distance([*a, *b])),
+            [a, b, c] => Some(Measurement::Angle([*a, *b, *c])),
+            _ => None,
+        }
+    }
+
+    pub fn points(&self) -> &[Vector3<f32>] {
+        match self {
+            Measurement::Distance(points) => points,
+            Measurement::Angle(points) => points,
+        }
+    }
+
+    /// The points of the measurement in world coordinates.
+    pub fn world_points(&self, model: &Model) -> Vec<Vector3<f32>> {
+        let world = world_matrix(model);
+        self.points().iter()
+            .map(|p| world.transform_point(&Point3::from(*p)).coords)
+            .collect()
+    }
+
+    /// The measured value: A distance in the selected unit, respecting the scaling of the object,
+    /// or an angle in degrees.
+    pub fn value(&self, model: &Model) -> f32 {
+        match self {
+            Measurement::Distance([a, b]) => {
+                let length = (b - a).norm() * model.scaling_factor;
+                model.model_unit.convert(length, model.unit)
+            }
+            Measurement::Angle([a, b, c]) => (a - b).angle(&(c - b)).to_degrees(),
+        }
+    }
+
+    /// The value together with its unit, as it is presented to the user, with the decimals of the
+    /// HUD.
+    pub fn label(&self, model: &Model) -> String {
+        let value = model.hud_format.number(self.value(model));
+        match self {
+            Measurement::Distance(_) => format!("{value} {}", model.unit),
+            Measurement::Angle(_) => format!("{value}°"),
+        }
+    }
+
+    /// The point, where the label of the measurement is placed, in world coordinates.
+    pub fn label_position(&self, model: &Model) -> Vector3<f32> {
+        let points = self.world_points(model);
+        match self {
+            Measurement::Distance(_) => (points[0] + points[1]) / 2.0,
+            Measurement::Angle(_) => points[1],
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Measurement::Distance(_) => "distance",
+            Measurement::Angle(_) => "angle",
+        }
+    }
+}
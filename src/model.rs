@@ -1,22 +1,79 @@
-use nalgebra::Vector3;
+use std::time::Instant;
+
+use nalgebra::{Point3, Vector3};
 use obj::Obj;
 
-/// The vertical increment
-const VERTICAL_INCR: f32 = 0.1;
+use crate::bounds::{BoundingBox, OrientedBox};
+use crate::changes::{Change, Changes};
+use crate::console::Console;
+use crate::camera::{Lens, Projection, StandardView, Transition};
+use crate::export::{ExportRequest, ExportSettings};
+use crate::format::HudFormat;
+use crate::history::{History, StepKind};
+use crate::lighting::{Light, LightKind, LightPreset, Lighting};
+use crate::hud::HudStyle;
+use crate::measure::{Measurement, MeasureMode};
+use crate::navigation::{self, Navigation};
+use crate::picking::{Bvh, Pick};
+use crate::units::Unit;
+use crate::reference::AXES_LENGTH;
+use crate::session::{Bookmark, BOOKMARK_SLOTS};
+use crate::statistics::MeshStatistics;
+use crate::view::{OBJECT_HOME, world_matrix};
+
+/// The speed, by which the viewer moves up and down, in units per second.
+pub const VERTICAL_SPEED: f32 = 1.0;
+
+/// The speed, by which the viewer moves horizontally, in units per second.
+pub const VIEW_SPEED: f32 = 2.0;
+
+// The speed, by which the object rotates, in RAD per second.
+pub const ROTATION_SPEED: f32 = std::f32::consts::FRAC_PI_2;
+
+// The speed, by which the object moves along one axis, in units per second.
+pub const MOVE_SPEED: f32 = 2.0;
+
+// The factor, by which the object grows within one second.
+pub const SCALE_SPEED: f32 = 2.0;
+
+/// The distance, by which the viewer moves up or down with a single tap of a key.
+pub const VERTICAL_STEP: f32 = 0.1;
+
+/// The distance, by which the viewer moves horizontally with a single tap of a key.
+pub const VIEW_STEP: f32 = 0.2;
+
+// The angle in RAD, by which the object rotates with a single tap of a key.
+pub const ROTATION_STEP: f32 = std::f32::consts::PI / 20.0;
+
+// The distance, by which the object moves along one axis with a single tap of a key.
+pub const MOVE_STEP: f32 = 1.0;
+
+// The factor, by which the object grows with a single tap of a key.
+pub const SCALE_STEP: f32 = 2.0;
 
-// The increment, we allow to rotate the object in RAD.
-const ROTATION_INCR: f32 = std::f32::consts::PI / 20.0;
+// The space left around the object, when the viewer is placed in front of it.
+const FRAMING_MARGIN: f32 = 1.1;
 
-// The increment by which the object will be moved along one axis.
-const MOVE_INCR: f32 = 1.00;
+// The half edge length of the ground plane relative to the extent of the object.
+const GROUND_SIZE: f32 = 2.0;
 
 pub type Vertex = obj::Vertex;
 
+/// A named part of the object, like a group of the obj file. The triangles of the part are given
+/// as ranges of triangle indices.
+#[derive(Clone, Debug)]
+pub struct Group {
+    pub name: String,
+    pub triangles: Vec<std::ops::Range<usize>>,
+}
+
 /// This model manages the different system states, which will be manipulated by the controller.
 /// It starts with the camara but must also contain information regarding the orientation of the
 /// graphic model, under investigation..
 pub struct Model {
-    changed: bool,
+    changes: Changes,
+    /// The obj file, the object was loaded from.
+    pub file_name: String,
     pub object: Obj,
     pub scaling_factor: f32,
     pub rot: [f32; 3],
@@ -24,7 +81,54 @@ pub struct Model {
     pub view_position: [f32; 3],
     pub view_direction: [f32; 3],
     pub up: [f32; 3],
-
+    pub navigation: Navigation,
+    pub projection: Projection,
+    pub lens: Lens,
+    transition: Option<Transition>,
+    pub extent: f32,
+    pub show_grid: bool,
+    pub show_axes: bool,
+    pub show_gizmo: bool,
+    pub bounds: BoundingBox,
+    pub oriented_bounds: OrientedBox,
+    pub show_bounds: bool,
+    pub show_oriented_bounds: bool,
+    /// Lets the key light cast shadows. Software renderers start without them.
+    pub show_shadows: bool,
+    /// Softens the edges of the shadows by percentage closer filtering.
+    pub soft_shadows: bool,
+    /// Shows a plane right below the object, which receives its shadow.
+    pub show_ground: bool,
+    pub show_help: bool,
+    pub show_object_section: bool,
+    pub show_camera_section: bool,
+    pub show_direction_section: bool,
+    pub show_statistics_section: bool,
+    pub show_performance_section: bool,
+    /// Draws a frame as often as possible instead of only after changes, e.g. to measure the
+    /// frame rate.
+    pub render_continuously: bool,
+    pub hud_format: HudFormat,
+    pub hud_style: HudStyle,
+    pub console: Console,
+    pub lighting: Lighting,
+    pub model_unit: Unit,
+    pub unit: Unit,
+    pub groups: Vec<Group>,
+    pub statistics: MeshStatistics,
+    pub bvh: Bvh,
+    pub hover: Option<Pick>,
+    pub selection: Option<Pick>,
+    pub measure_mode: MeasureMode,
+    pub measure_points: Vec<Vector3<f32>>,
+    pub measurements: Vec<Measurement>,
+    pub export: ExportSettings,
+    export_request: Option<ExportRequest>,
+    /// The stored camera and object states, recalled by their number.
+    pub bookmarks: [Option<Bookmark>; BOOKMARK_SLOTS],
+    /// The session file, which is written, when the session is saved.
+    pub session_file: Option<std::path::PathBuf>,
+    history: History,
 }
 impl Model {
     /// Creates a new model with a reset on the coordinates.
@@ -34,143 +138,834 @@ impl Model {
         let view_position = [3.0, 1.0, 1.0];
         let view_direction = [-3.0, -1.0, 1.0];
         let up = [0.0, 1.0, 0.0];
+        let start = Instant::now();
         let input = std::fs::read(file_name)?;
-        let rh_object = obj::load_obj(input.as_slice())?;
+        let raw = obj::raw::parse_obj(input.as_slice())?;
+        let groups = groups(&raw.groups);
+        let mut statistics = MeshStatistics::new(&input, &raw, groups.len());
+        let rh_object = Obj::new(raw)?;
         let object = to_left_handed(&rh_object);
         let object_position = Vector3::from([0.0, 0.0, 0.0]);
-        Ok(Self { changed: true, object, scaling_factor, rot, object_position, view_position, view_direction, up })
+        let extent = extent(&object);
+        let bounds = BoundingBox::from_vertices(&object.vertices);
+        let oriented_bounds = OrientedBox::from_vertices(&object.vertices);
+        let bvh = Bvh::new(&object.vertices, &object.indices);
+        statistics.count_object(&object, start.elapsed());
+        Ok(Self { changes: Changes::default(), file_name: file_name.to_string(), object, scaling_factor, rot, object_position, view_position, view_direction, up,
+                  navigation: Navigation::Orbit, projection: Projection::Perspective,
+                  lens: Lens::default(), transition: None, extent, show_grid: false, show_axes: false, show_gizmo: true,
+                  bounds, oriented_bounds, show_bounds: false, show_oriented_bounds: false,
+                  show_shadows: true, soft_shadows: true, show_ground: false, show_help: false,
+                  show_object_section: true, show_camera_section: true, show_direction_section: true,
+                  show_statistics_section: true, show_performance_section: false, render_continuously: false,
+                  hud_format: HudFormat::default(), hud_style: HudStyle::default(),
+                  console: Console::default(), lighting: Lighting::default(),
+                  model_unit: Unit::Meter, unit: Unit::Meter,
+                  groups, statistics, bvh, hover: None, selection: None,
+                  measure_mode: MeasureMode::Off, measure_points: Vec::new(), measurements: Vec::new(),
+                  export: ExportSettings::default(), export_request: None,
+                  bookmarks: [None; BOOKMARK_SLOTS], session_file: None, history: History::default() })
+    }
+
+    /// Replaces the object by the object of another obj file. The transform of the object and the
+    /// camera are kept, the picks and the measurements of the old object are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * 'file_name' - The obj file to load.
+    pub fn load(&mut self, file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let loaded = Self::new(file_name)?;
+        self.file_name = loaded.file_name;
+        self.object = loaded.object;
+        self.extent = loaded.extent;
+        self.bounds = loaded.bounds;
+        self.oriented_bounds = loaded.oriented_bounds;
+        self.groups = loaded.groups;
+        self.statistics = loaded.statistics;
+        self.bvh = loaded.bvh;
+        self.hover = None;
+        self.selection = None;
+        self.measure_points.clear();
+        self.measurements.clear();
+        self.notify(Change::Geometry);
+        self.notify(Change::Display);
+        Ok(())
+    }
+
+    /// Tells the observers of the model about a change.
+    pub fn notify(&mut self, change: Change) {
+        self.changes.notify(change);
+    }
+
+    /// The changes of the model, which the observers compare with their subscriptions.
+    pub fn changes(&self) -> &Changes {
+        &self.changes
+    }
+
+    /// The extent of the object in world coordinates, i.e. after the scaling was applied.
+    pub fn world_extent(&self) -> f32 {
+        self.extent * self.scaling_factor
+    }
+
+    /// Shows or hides the ground grid below the object.
+    pub fn toggle_grid(&mut self) {
+        self.show_grid = !self.show_grid;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the axes of the world coordinate system.
+    pub fn toggle_axes(&mut self) {
+        self.show_axes = !self.show_axes;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the small orientation gizmo in the corner of the window.
+    pub fn toggle_gizmo(&mut self) {
+        self.show_gizmo = !self.show_gizmo;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the axis-aligned bounding box together with the dimension labels.
+    pub fn toggle_bounds(&mut self) {
+        self.show_bounds = !self.show_bounds;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the oriented bounding box.
+    pub fn toggle_oriented_bounds(&mut self) {
+        self.show_oriented_bounds = !self.show_oriented_bounds;
+        self.notify(Change::Display);
+    }
+
+    /// Turns the shadows of the key light on or off.
+    pub fn toggle_shadows(&mut self) {
+        self.show_shadows = !self.show_shadows;
+        self.notify(Change::Display);
+    }
+
+    /// Switches between the soft, filtered edges of the shadows and the hard ones.
+    pub fn toggle_soft_shadows(&mut self) {
+        self.soft_shadows = !self.soft_shadows;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the ground plane below the object.
+    pub fn toggle_ground(&mut self) {
+        self.show_ground = !self.show_ground;
+        self.notify(Change::Display);
+    }
+
+    /// The ground plane touches the lowest corner of the bounding box of the object and lies
+    /// centered below it.
+    ///
+    /// Returns the center of the plane in world coordinates and half of its edge length.
+    pub fn ground_plane(&self) -> (Vector3<f32>, f32) {
+        let world = world_matrix(self);
+        let corners = self.bounds.corners().map(|corner| world.transform_point(&Point3::from(corner)));
+        let bottom = corners.iter().map(|corner| corner.y).fold(f32::INFINITY, f32::min);
+        let center = world.transform_point(&Point3::from((self.bounds.min + self.bounds.max) / 2.0));
+        (Vector3::new(center.x, bottom, center.z), GROUND_SIZE * self.world_extent())
+    }
+
+    /// Shows or hides the help with the key bindings.
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the section of the HUD with the position, rotation and scale of the object.
+    pub fn toggle_object_section(&mut self) {
+        self.show_object_section = !self.show_object_section;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the section of the HUD with the navigation, projection and position of the
+    /// viewer.
+    pub fn toggle_camera_section(&mut self) {
+        self.show_camera_section = !self.show_camera_section;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the section of the HUD with the line of sight.
+    pub fn toggle_direction_section(&mut self) {
+        self.show_direction_section = !self.show_direction_section;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the section of the HUD with the statistics of the loaded mesh.
+    pub fn toggle_statistics_section(&mut self) {
+        self.show_statistics_section = !self.show_statistics_section;
+        self.notify(Change::Display);
+    }
+
+    /// Shows or hides the section of the HUD with the frame rate and the frame times.
+    pub fn toggle_performance_section(&mut self) {
+        self.show_performance_section = !self.show_performance_section;
+        self.notify(Change::Display);
+    }
+
+    /// Switches between drawing as often as possible and drawing only after changes.
+    pub fn toggle_continuous_rendering(&mut self) {
+        self.render_continuously = !self.render_continuously;
+        self.notify(Change::Display);
+    }
+
+    /// Opens or closes the command console.
+    pub fn toggle_console(&mut self) {
+        self.console.toggle();
+        self.notify(Change::Display);
+    }
+
+    /// Replaces the lights of the scene.
+    pub fn set_lighting(&mut self, lighting: Lighting) {
+        self.lighting = lighting;
+        self.notify(Change::Display);
+    }
+
+    /// Changes the lights of the scene. The lights no longer belong to a preset afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * 'edit' - Changes the lighting. Errors leave the lighting, as it was.
+    pub fn edit_lighting<F>(&mut self, edit: F) -> Result<(), String>
+        where F: FnOnce(&mut Lighting) -> Result<(), String> {
+        let mut lighting = self.lighting.clone();
+        edit(&mut lighting)?;
+        lighting.preset = None;
+        self.set_lighting(lighting);
+        Ok(())
+    }
+
+    /// Sets the direction, from which the key light falls onto the object. It becomes a
+    /// directional light.
+    pub fn set_light(&mut self, direction: [f32; 3]) {
+        let _ = self.edit_lighting(|lighting| {
+            match lighting.lights.first_mut() {
+                Some(light) => {
+                    light.kind = LightKind::Directional;
+                    light.vector = direction;
+                },
+                None => lighting.lights.push(Light::directional(direction, [1.0, 1.0, 1.0], 1.0)),
+            }
+            Ok(())
+        });
+    }
+
+    /// Switches to the next preset of the lights.
+    pub fn next_light_preset(&mut self) {
+        let preset = self.lighting.preset.map_or(LightPreset::Classic, |preset| preset.next());
+        self.set_lighting(preset.lighting());
+    }
+
+    /// Attaches the key light to the camera or leaves it in the scene. The light keeps the
+    /// direction, from which it currently falls.
+    pub fn set_key_light_on_camera(&mut self, on_camera: bool) {
+        let (direction, up, position) = (self.view_direction, self.up, self.view_position);
+        let _ = self.edit_lighting(|lighting| {
+            lighting.set_key_on_camera(on_camera, &direction, &up, &position);
+            Ok(())
+        });
+    }
+
+    /// Attaches the key light to the camera or detaches it.
+    pub fn toggle_key_light_on_camera(&mut self) {
+        self.set_key_light_on_camera(!self.lighting.key_on_camera);
+    }
+
+    /// Sets the unit, in which dimensions are presented.
+    pub fn set_unit(&mut self, unit: Unit) {
+        self.unit = unit;
+        self.notify(Change::Display);
+    }
+
+    /// Sets, how the values of the HUD are formatted.
+    pub fn set_hud_format(&mut self, format: HudFormat) {
+        self.hud_format = format;
+        self.notify(Change::Display);
+    }
+
+    /// Switches to the next unit, in which dimensions are presented.
+    pub fn next_unit(&mut self) {
+        self.unit = self.unit.next();
+        self.notify(Change::Display);
+    }
+
+    /// The name of the group, the triangle belongs to. Without groups, this is the name of the
+    /// object.
+    pub fn group_of(&self, triangle: usize) -> Option<&str> {
+        self.groups.iter()
+            .find(|group| group.triangles.iter().any(|range| range.contains(&triangle)))
+            .map(|group| group.name.as_str())
+            .or(self.object.name.as_deref())
+    }
+
+    /// Sets the triangle under the cursor.
+    pub fn set_hover(&mut self, pick: Option<Pick>) {
+        if self.hover != pick {
+            self.hover = pick;
+            self.notify(Change::Display);
+        }
+    }
+
+    /// Selects the triangle under the cursor. Selecting the background clears the selection. In
+    /// the measurement mode, the point under the cursor is added to the measurement instead.
+    pub fn select(&mut self) {
+        if self.measure_mode != MeasureMode::Off {
+            self.add_measure_point();
+        } else if self.selection != self.hover {
+            self.selection = self.hover.clone();
+            self.notify(Change::Display);
+        }
+    }
+
+    /// Switches to the next measurement mode. Points of an unfinished measurement are dropped.
+    pub fn next_measure_mode(&mut self) {
+        self.measure_mode = self.measure_mode.next();
+        self.measure_points.clear();
+        self.notify(Change::Display);
+    }
+
+    /// Adds the surface point under the cursor to the measurement in progress. As soon as there
+    /// are enough points, the measurement is finished.
+    fn add_measure_point(&mut self) {
+        let pick = if let Some(pick) = &self.hover { pick } else { return };
+        self.measure_points.push(pick.local_position);
+        if self.measure_points.len() >= self.measure_mode.points() {
+            if let Some(measurement) = Measurement::from_points(&self.measure_points) {
+                self.measurements.push(measurement);
+            }
+            self.measure_points.clear();
+        }
+        self.notify(Change::Display);
     }
 
-    pub fn changed(&mut self) {
-        self.changed = true;
+    /// Removes the measurement in progress or, if there is none, the last measurement.
+    pub fn remove_last_measurement(&mut self) {
+        if self.measure_points.is_empty() {
+            self.measurements.pop();
+        } else {
+            self.measure_points.clear();
+        }
+        self.notify(Change::Display);
     }
 
-    pub fn reset_changed(&mut self) {
-        self.changed = false;
+    /// Requests an image of the current picture. The image is rendered after the next frame.
+    pub fn request_export(&mut self, request: ExportRequest) {
+        self.export_request = Some(request);
     }
 
-    pub fn has_changed(&self) -> bool {
-        self.changed
+    /// Takes the pending image request, so it is handled only once.
+    pub fn take_export_request(&mut self) -> Option<ExportRequest> {
+        self.export_request.take()
+    }
+
+    /// Stores the current camera and object transform in the bookmark with the provided number.
+    ///
+    /// # Arguments
+    ///
+    /// * 'number' - The number of the bookmark, starting at 1.
+    pub fn store_bookmark(&mut self, number: usize) {
+        let bookmark = Bookmark::of(self);
+        if let Some(slot) = self.bookmarks.get_mut(number.wrapping_sub(1)) {
+            *slot = Some(bookmark);
+            log::info!("Bookmark {number} stored");
+        }
+    }
+
+    /// Puts the camera and the object back into the state of the bookmark with the provided
+    /// number. Empty bookmarks are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * 'number' - The number of the bookmark, starting at 1.
+    pub fn recall_bookmark(&mut self, number: usize) {
+        match self.bookmarks.get(number.wrapping_sub(1)).copied().flatten() {
+            Some(bookmark) => {
+                self.record(StepKind::Jump);
+                self.transition = None;
+                bookmark.apply(self);
+            },
+            None => log::info!("Bookmark {number} is empty"),
+        }
+    }
+
+    /// The pick, which is presented to the user: The selection or the triangle under the cursor,
+    /// if nothing is selected.
+    pub fn current_pick(&self) -> Option<&Pick> {
+        self.selection.as_ref().or(self.hover.as_ref())
     }
 
     /// Resets the viewers position to the original.
     pub fn reset_view(&mut self) {
+        self.record(StepKind::Jump);
         self.view_position = [3.0, 1.0, 1.0];
         self.view_direction = [-3.0, -1.0, 1.0];
         self.up = [0.0, 1.0, 0.0];
-        self.changed = true;
+        self.transition = None;
+        self.notify(Change::Camera);
+    }
+
+    /// Resets the rotation, the scaling and the position of the object.
+    pub fn reset_object(&mut self) {
+        self.record(StepKind::Jump);
+        self.rot = [0.0, 0.0, 0.0];
+        self.scaling_factor = 1.0;
+        self.object_position = Vector3::zeros();
+        self.notify(Change::Transform);
+    }
+
+    /// Records the camera and the object transform before a change, so it may be undone.
+    fn record(&mut self, kind: StepKind) {
+        self.history.record(kind, Bookmark::of(self));
+    }
+
+    /// Ends the current gesture, so the next change becomes a step of its own in the history.
+    pub fn end_step(&mut self) {
+        self.history.end_step();
+    }
+
+    /// Undoes the last change of the camera or the object transform.
+    pub fn undo(&mut self) {
+        match self.history.undo(Bookmark::of(self)) {
+            Some(state) => {
+                self.transition = None;
+                state.apply(self);
+            },
+            None => log::info!("Nothing to undo"),
+        }
+    }
+
+    /// Redoes the last undone change of the camera or the object transform.
+    pub fn redo(&mut self) {
+        match self.history.redo(Bookmark::of(self)) {
+            Some(state) => {
+                self.transition = None;
+                state.apply(self);
+            },
+            None => log::info!("Nothing to redo"),
+        }
+    }
+
+    /// The point, the viewer looks at in the orbit mode and the standard views: The origin of the
+    /// object.
+    pub fn target(&self) -> Vector3<f32> {
+        Vector3::from(OBJECT_HOME) + self.object_position
+    }
+
+    /// The direction of the line of sight with a length of one.
+    pub fn line_of_sight(&self) -> Vector3<f32> {
+        Vector3::from(self.view_direction).try_normalize(f32::EPSILON).unwrap_or_else(Vector3::z)
+    }
+
+    /// The distance of the target from the viewer along the line of sight. The orthographic
+    /// projection shows the same extent at this distance as the perspective does.
+    pub fn target_distance(&self) -> f32 {
+        (self.target() - Vector3::from(self.view_position)).dot(&self.line_of_sight()).abs().max(f32::EPSILON)
+    }
+
+    /// The sphere around the target, which contains everything, which is drawn into the scene:
+    /// The object and, if they are shown, the grid, the axes and the ground plane.
+    pub fn scene_sphere(&self) -> (Vector3<f32>, f32) {
+        let center = self.target();
+        let extent = self.world_extent();
+        let mut radius = extent;
+        if self.show_grid {
+            // The grid covers a square of about twice the extent plus the distance to the origin
+            // around the home position of the object, see reference::grid_layout().
+            let grid_center = Vector3::new(OBJECT_HOME[0], OBJECT_HOME[1] - extent, OBJECT_HOME[2]);
+            let grid_radius = std::f32::consts::SQRT_2 * (2.5 * extent + OBJECT_HOME[2].abs());
+            radius = radius.max((grid_center - center).norm() + grid_radius);
+        }
+        if self.show_axes {
+            radius = radius.max(center.norm() + AXES_LENGTH * extent);
+        }
+        if self.show_ground {
+            let (ground_center, half_size) = self.ground_plane();
+            radius = radius.max((ground_center - center).norm() + std::f32::consts::SQRT_2 * half_size);
+        }
+        (center, radius)
+    }
+
+    /// The near and the far plane, which are used to render the scene. In the automatic mode,
+    /// they are fitted to the scene sphere.
+    pub fn clip_range(&self) -> (f32, f32) {
+        let (center, radius) = self.scene_sphere();
+        let depth = (center - Vector3::from(self.view_position)).dot(&self.line_of_sight());
+        self.lens.clip_range(depth, radius, self.projection)
+    }
+
+    /// Changes the field of view by the provided angle.
+    pub fn widen_field_of_view(&mut self, angle: f32) {
+        self.lens.set_field_of_view(self.lens.field_of_view + angle);
+        self.notify(Change::Camera);
+    }
+
+    /// Moves the near plane by the provided factor. The fixed planes replace the automatic ones.
+    pub fn scale_near(&mut self, factor: f32) {
+        self.fix_clip_range();
+        self.lens.near = (self.lens.near * factor).min(self.lens.far / 2.0);
+        self.notify(Change::Camera);
+    }
+
+    /// Moves the far plane by the provided factor. The fixed planes replace the automatic ones.
+    pub fn scale_far(&mut self, factor: f32) {
+        self.fix_clip_range();
+        self.lens.far = (self.lens.far * factor).max(self.lens.near * 2.0);
+        self.notify(Change::Camera);
+    }
+
+    /// Switches between the automatic and the fixed clip range.
+    pub fn toggle_auto_clip(&mut self) {
+        if self.lens.auto_clip {
+            self.fix_clip_range();
+        } else {
+            self.lens.auto_clip = true;
+        }
+        self.notify(Change::Camera);
+    }
+
+    /// Turns the automatic clip range of the perspective into the fixed one, so the user starts
+    /// to change the planes from, what is currently seen.
+    fn fix_clip_range(&mut self) {
+        if self.lens.auto_clip {
+            let projection = self.projection;
+            self.projection = Projection::Perspective;
+            (self.lens.near, self.lens.far) = self.clip_range();
+            self.projection = projection;
+            self.lens.auto_clip = false;
+        }
+    }
+
+    /// Sets the field of view, which is limited to the range of the lens.
+    ///
+    /// # Arguments
+    ///
+    /// * 'angle' - The field of view in RAD.
+    pub fn set_field_of_view(&mut self, angle: f32) {
+        self.lens.set_field_of_view(angle);
+        self.notify(Change::Camera);
+    }
+
+    /// Sets the projection of the scene onto the screen.
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+        self.notify(Change::Camera);
+    }
+
+    /// Switches between the perspective and the orthographic projection.
+    pub fn toggle_projection(&mut self) {
+        self.projection = self.projection.next();
+        self.notify(Change::Camera);
+    }
+
+    /// Moves the viewer smoothly into a standard view of the object. The distance to the object
+    /// is kept.
+    pub fn show_standard_view(&mut self, view: StandardView) {
+        self.record(StepKind::Jump);
+        let (offset, up) = view.orientation();
+        self.transition = Some(Transition::new(self.target(),
+                                               &Vector3::from(self.view_position),
+                                               &Vector3::from(self.view_direction),
+                                               &Vector3::from(self.up),
+                                               &offset, &up));
+        self.notify(Change::Camera);
+    }
+
+    /// Places the viewer at a position, from which it looks at a target. The viewer jumps there
+    /// without a transition, so the new view may be exported at once.
+    ///
+    /// # Arguments
+    ///
+    /// * 'position' - The new position of the viewer.
+    /// * 'target' - The point, the viewer looks at. Without a target, the viewer looks at the
+    ///   object.
+    pub fn place_camera(&mut self, position: Vector3<f32>, target: Option<Vector3<f32>>) {
+        self.record(StepKind::Jump);
+        let direction = target.unwrap_or_else(|| self.target()) - position;
+        if let Some(direction) = direction.try_normalize(f32::EPSILON) {
+            self.view_direction = direction.into();
+            // Looking straight down or up, the back of the object is at the top of the screen,
+            // like in the standard views.
+            self.up = if direction.cross(&Vector3::y()).norm() < 1.0e-3 { [0.0, 0.0, 1.0] } else { [0.0, 1.0, 0.0] };
+        }
+        self.view_position = position.into();
+        self.transition = None;
+        self.notify(Change::Camera);
+    }
+
+    /// Advances a running movement of the viewer.
+    ///
+    /// # Arguments
+    ///
+    /// * 'seconds' - The time since the last frame.
+    pub fn animate(&mut self, seconds: f32) {
+        if let Some(transition) = &mut self.transition {
+            (self.view_position, self.view_direction, self.up) = transition.advance(seconds);
+            if transition.is_finished() {
+                self.transition = None;
+            }
+            self.notify(Change::Camera);
+        }
+    }
+
+    /// The standard views may turn the up vector away from the vertical. Before the viewer is
+    /// turned by hand again, the up vector becomes vertical again. A line of sight, which is
+    /// exactly vertical, is tilted slightly towards the old up vector, so the picture does not
+    /// flip around.
+    fn level_up(&mut self) {
+        let up = Vector3::from(self.up);
+        if up != Vector3::y() {
+            let direction = Vector3::from(self.view_direction).normalize() + up * 1.0e-3;
+            self.view_direction = direction.into();
+            self.up = [0.0, 1.0, 0.0];
+        }
+        self.transition = None;
+    }
+
+    /// Places the viewer in front of the object, so the complete object is visible. The viewer
+    /// looks at the origin of the object from the provided elevation.
+    ///
+    /// # Arguments
+    ///
+    /// * 'elevation' - The angle between the line of sight and the ground in radians.
+    /// * 'aspect_ratio' - The width of the picture divided by its height.
+    pub fn frame_object(&mut self, elevation: f32, aspect_ratio: f32) {
+        self.record(StepKind::Jump);
+        let limit = 89.0f32.to_radians();
+        let elevation = elevation.clamp(-limit, limit);
+        let half_fov = (self.lens.field_of_view / 2.0).tan();
+        let half_fov = half_fov.min(half_fov * aspect_ratio).atan();
+        let radius = self.world_extent().max(f32::EPSILON);
+        let distance = FRAMING_MARGIN * radius / half_fov.sin();
+
+        let target = self.target();
+        let offset = Vector3::new(0.0, elevation.sin(), -elevation.cos()) * distance;
+        self.view_position = (target + offset).into();
+        self.view_direction = (-offset).into();
+        self.up = [0.0, 1.0, 0.0];
+        self.transition = None;
+        self.notify(Change::Camera);
+    }
+
+    /// Switches between orbiting around the object and flying through the scene.
+    pub fn toggle_navigation(&mut self) {
+        self.navigation = self.navigation.next();
+        self.notify(Change::Display);
+    }
+
+    /// Sets the navigation mode.
+    pub fn set_navigation(&mut self, navigation: Navigation) {
+        self.navigation = navigation;
+        self.notify(Change::Display);
+    }
+
+    /// Moves the viewer relative to the line of sight. This is used to fly through the scene.
+    ///
+    /// # Arguments
+    ///
+    /// * 'forward' - The distance along the line of sight.
+    /// * 'right' - The distance to the right of the viewer.
+    /// * 'up' - The distance upwards, i.e. along the up vector of the world.
+    pub fn fly(&mut self, forward: f32, right: f32, up: f32) {
+        self.record(StepKind::Camera);
+        let direction = self.line_of_sight();
+        let world_up = Vector3::from(self.up);
+        let offset = direction * forward + navigation::right(&direction, &world_up) * right + world_up * up;
+        self.view_position = (Vector3::from(self.view_position) + offset).into();
+        self.notify(Change::Camera);
+    }
+
+    /// Turns the line of sight, while the viewer stays in place.
+    ///
+    /// # Arguments
+    ///
+    /// * 'yaw' - The angle to turn to the right in RAD.
+    /// * 'pitch' - The angle to turn upwards in RAD.
+    pub fn look(&mut self, yaw: f32, pitch: f32) {
+        self.record(StepKind::Camera);
+        self.level_up();
+        let (old_yaw, old_pitch) = navigation::yaw_pitch(&Vector3::from(self.view_direction));
+        self.view_direction = navigation::direction(old_yaw + yaw, old_pitch + pitch).into();
+        self.notify(Change::Camera);
+    }
+
+    /// Moves the viewer on a sphere around the object, while it keeps looking at the object.
+    ///
+    /// # Arguments
+    ///
+    /// * 'yaw' - The angle to circle around the vertical axis in RAD.
+    /// * 'pitch' - The angle to circle upwards in RAD.
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        self.record(StepKind::Camera);
+        let target = self.target();
+        let mut offset = Vector3::from(self.view_position) - target;
+        if self.up != [0.0, 1.0, 0.0] {
+            offset -= Vector3::from(self.up) * (offset.norm() * 1.0e-3);
+        }
+        self.level_up();
+        let (old_yaw, old_pitch) = navigation::yaw_pitch(&offset);
+        let offset = navigation::direction(old_yaw + yaw, old_pitch + pitch) * offset.norm();
+        self.view_position = (target + offset).into();
+        self.view_direction = (-offset).into();
+        self.notify(Change::Camera);
     }
 
     /// Move the viewers position up.
-    pub fn view_position_up(&mut self) {
-        self.view_position[1] += VERTICAL_INCR;
-        self.changed();
+    pub fn view_position_up(&mut self, distance: f32) {
+        self.record(StepKind::Camera);
+        self.view_position[1] += distance;
+        self.notify(Change::Camera);
     }
 
     /// Move the viewers position down.
-    pub fn view_position_down(&mut self) {
-        self.view_position[1] -= VERTICAL_INCR;
-        self.changed();
+    pub fn view_position_down(&mut self, distance: f32) {
+        self.record(StepKind::Camera);
+        self.view_position[1] -= distance;
+        self.notify(Change::Camera);
     }
 
     /// Move the viewers position forward.
-    pub fn view_position_forward(&mut self) {
-        self.view_position[0] -= 0.2;
-        self.changed();
+    pub fn view_position_forward(&mut self, distance: f32) {
+        self.record(StepKind::Camera);
+        self.view_position[0] -= distance;
+        self.notify(Change::Camera);
     }
 
     /// Move the viewers position backward.
-    pub fn view_position_backward(&mut self) {
-        self.view_position[0] += 0.2;
-        self.changed();
+    pub fn view_position_backward(&mut self, distance: f32) {
+        self.record(StepKind::Camera);
+        self.view_position[0] += distance;
+        self.notify(Change::Camera);
     }
 
     /// Move the viewers position to the left.
-    pub fn view_position_left(&mut self) {
-        self.view_position[2] += 0.2;
-        self.changed();
+    pub fn view_position_left(&mut self, distance: f32) {
+        self.record(StepKind::Camera);
+        self.view_position[2] += distance;
+        self.notify(Change::Camera);
     }
 
     /// Move the viewers position to the right.
-    pub fn view_position_right(&mut self) {
-        self.view_position[2] -= 0.2;
-        self.changed();
+    pub fn view_position_right(&mut self, distance: f32) {
+        self.record(StepKind::Camera);
+        self.view_position[2] -= distance;
+        self.notify(Change::Camera);
     }
 
     /// Rols the object up. This is a rotation around the X-Axis (Eula roll).
-    pub fn roll_up(&mut self) {
-        self.rot[0] += ROTATION_INCR;
-        self.changed();
+    pub fn roll_up(&mut self, angle: f32) {
+        self.record(StepKind::Rotate);
+        self.rot[0] += angle;
+        self.notify(Change::Transform);
     }
 
     /// Rols the object down. This is a rotation around the X-Axis (Eula roll).
-    pub fn roll_down(&mut self) {
-        self.rot[0] -= ROTATION_INCR;
-        self.changed();
+    pub fn roll_down(&mut self, angle: f32) {
+        self.record(StepKind::Rotate);
+        self.rot[0] -= angle;
+        self.notify(Change::Transform);
     }
 
     /// Rotate the object to the left. This is a rotation around the Y-Axis (Eula pitch).
-    pub fn rotate_left(&mut self) {
-        self.rot[1] += ROTATION_INCR;
-        self.changed();
+    pub fn rotate_left(&mut self, angle: f32) {
+        self.record(StepKind::Rotate);
+        self.rot[1] += angle;
+        self.notify(Change::Transform);
     }
 
     /// Rotate the object to the right. This is a rotation around the Y-Axis (Eula pitch).
-    pub fn rotate_right(&mut self) {
-        self.rot[1] -= ROTATION_INCR;
-        self.changed();
+    pub fn rotate_right(&mut self, angle: f32) {
+        self.record(StepKind::Rotate);
+        self.rot[1] -= angle;
+        self.notify(Change::Transform);
     }
 
     /// Rotate the object up. This is a rotation around the Z-Axis (Eula yaw).
-    pub fn rotate_up(&mut self) {
-        self.rot[2] += ROTATION_INCR;
-        self.changed();
+    pub fn rotate_up(&mut self, angle: f32) {
+        self.record(StepKind::Rotate);
+        self.rot[2] += angle;
+        self.notify(Change::Transform);
     }
 
     /// Rotate the object to the right. This is a rotation around the Z-Axis (Eula yaw).
-    pub fn rotate_down(&mut self) {
-        self.rot[2] -= ROTATION_INCR;
-        self.changed();
+    pub fn rotate_down(&mut self, angle: f32) {
+        self.record(StepKind::Rotate);
+        self.rot[2] -= angle;
+        self.notify(Change::Transform);
+    }
+
+    /// Rotates the object around one of its axes by an angle in RAD.
+    ///
+    /// # Arguments
+    ///
+    /// * 'axis' - The axis: 0 for x, 1 for y and 2 for z.
+    /// * 'angle' - The angle in RAD.
+    pub fn rotate_around(&mut self, axis: usize, angle: f32) {
+        self.record(StepKind::Jump);
+        self.rot[axis] += angle;
+        self.notify(Change::Transform);
+    }
+
+    /// Sets the scaling factor of the object.
+    pub fn set_scaling_factor(&mut self, factor: f32) {
+        self.record(StepKind::Jump);
+        self.scaling_factor = factor;
+        self.notify(Change::Transform);
+    }
+
+    /// Moves the object by the provided offset.
+    pub fn move_object(&mut self, offset: Vector3<f32>) {
+        self.record(StepKind::Jump);
+        self.object_position += offset;
+        self.notify(Change::Transform);
     }
 
-    pub fn scale_up(&mut self) {
-        self.scaling_factor *= 2.0;
-        self.changed();
+    pub fn scale_up(&mut self, factor: f32) {
+        self.record(StepKind::Scale);
+        self.scaling_factor *= factor;
+        self.notify(Change::Transform);
     }
 
-    pub fn scale_down(&mut self) {
-        self.scaling_factor /= 2.0;
-        self.changed();
+    pub fn scale_down(&mut self, factor: f32) {
+        self.record(StepKind::Scale);
+        self.scaling_factor /= factor;
+        self.notify(Change::Transform);
     }
 
-    pub fn move_x_pos(&mut self) {
-        self.object_position[0] += MOVE_INCR;
-        self.changed();
+    pub fn move_x_pos(&mut self, distance: f32) {
+        self.record(StepKind::Move);
+        self.object_position[0] += distance;
+        self.notify(Change::Transform);
     }
 
-    pub fn move_x_neg(&mut self) {
-        self.object_position[0] -= MOVE_INCR;
-        self.changed();
+    pub fn move_x_neg(&mut self, distance: f32) {
+        self.record(StepKind::Move);
+        self.object_position[0] -= distance;
+        self.notify(Change::Transform);
     }
 
-    pub fn move_y_pos(&mut self) {
-        self.object_position[1] += MOVE_INCR;
-        self.changed();
+    pub fn move_y_pos(&mut self, distance: f32) {
+        self.record(StepKind::Move);
+        self.object_position[1] += distance;
+        self.notify(Change::Transform);
     }
 
-    pub fn move_y_neg(&mut self) {
-        self.object_position[1] -= MOVE_INCR;
-        self.changed();
+    pub fn move_y_neg(&mut self, distance: f32) {
+        self.record(StepKind::Move);
+        self.object_position[1] -= distance;
+        self.notify(Change::Transform);
     }
 
-    pub fn move_z_pos(&mut self) {
-        self.object_position[2] += MOVE_INCR;
-        self.changed();
+    pub fn move_z_pos(&mut self, distance: f32) {
+        self.record(StepKind::Move);
+        self.object_position[2] += distance;
+        self.notify(Change::Transform);
     }
 
-    pub fn move_z_neg(&mut self) {
-        self.object_position[2] -= MOVE_INCR;
-        self.changed();
+    pub fn move_z_neg(&mut self, distance: f32) {
+        self.record(StepKind::Move);
+        self.object_position[2] -= distance;
+        self.notify(Change::Transform);
     }
 }
 
@@ -198,3 +993,32 @@ fn to_left_handed(obj: &Obj) -> Obj {
 
     Obj { name, vertices, indices }
 }
+
+/// The extent of the object is the largest distance of one of its vertices from the origin of the
+/// object coordinates. Rotating the object never leaves a sphere with this radius.
+///
+/// # Arguments
+///
+/// * 'obj' - The object to measure.
+fn extent(obj: &Obj) -> f32 {
+    obj.vertices.iter()
+        .map(|v| Vector3::from(v.position).norm())
+        .fold(0.0, f32::max)
+}
+
+/// Collects the groups of the raw obj file, sorted by name.
+///
+/// # Arguments
+///
+/// * 'raw_groups' - The groups of the raw obj file, with the ranges of their polygons.
+fn groups(raw_groups: &std::collections::HashMap<String, obj::raw::object::Group>) -> Vec<Group> {
+    let mut groups: Vec<Group> = raw_groups.iter()
+        .map(|(name, group)| Group {
+            name: name.clone(),
+            triangles: group.polygons.iter().map(|range| range.start..range.end).collect(),
+        })
+        .filter(|group| !group.triangles.is_empty())
+        .collect();
+    groups.sort_by(|a, b| a.name.cmp(&b.name));
+    groups
+}
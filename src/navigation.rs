@@ -0,0 +1,44 @@
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+// The highest angle in RAD, the viewer may look up or down. Looking straight up or down would
+// make the up vector parallel to the line of sight.
+const PITCH_LIMIT: f32 = 89.0 * std::f32::consts::PI / 180.0;
+
+/// The navigation mode decides, how the viewer moves through the scene.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Navigation {
+    /// The viewer circles around the object, while looking at it.
+    Orbit,
+    /// The viewer flies in the direction of the line of sight and looks around with the mouse.
+    Fly,
+}
+impl Navigation {
+    /// The next mode in the cycle orbit -> fly -> orbit.
+    pub fn next(&self) -> Self {
+        match self {
+            Navigation::Orbit => Navigation::Fly,
+            Navigation::Fly => Navigation::Orbit,
+        }
+    }
+}
+
+/// The horizontal angle (yaw) and the vertical angle (pitch) of a direction in RAD. The yaw is
+/// measured from the z axis towards the x axis, the pitch from the ground upwards.
+pub fn yaw_pitch(direction: &Vector3<f32>) -> (f32, f32) {
+    let horizontal = (direction.x * direction.x + direction.z * direction.z).sqrt();
+    (direction.x.atan2(direction.z), direction.y.atan2(horizontal))
+}
+
+/// The direction of length one with the provided yaw and pitch. The pitch is limited, so the
+/// direction never becomes vertical.
+pub fn direction(yaw: f32, pitch: f32) -> Vector3<f32> {
+    let pitch = pitch.clamp(-PITCH_LIMIT, PITCH_LIMIT);
+    Vector3::new(pitch.cos() * yaw.sin(), pitch.sin(), pitch.cos() * yaw.cos())
+}
+
+/// The direction to the right of the viewer, in the same way as the view matrix computes it.
+pub fn right(direction: &Vector3<f32>, up: &Vector3<f32>) -> Vector3<f32> {
+    up.cross(direction).try_normalize(f32::EPSILON).unwrap_or_else(Vector3::x)
+}
@@ -0,0 +1,274 @@
+use std::path::PathBuf;
+
+use crate::camera::Lens;
+use crate::export::{ExportSettings, MAX_EXPORT_MULTIPLE};
+use crate::format::{HudFormat, MAX_DECIMALS};
+use crate::hud::HudStyle;
+use crate::lighting::LightPreset;
+use crate::thumbnails::ThumbnailSettings;
+use crate::turntable::TurntableSettings;
+use crate::units::Unit;
+
+const USAGE: &str = "Usage: glium-obj [--model-unit <unit>] [--unit <unit>] [--export-multiple <n>] \
+                     [--export-without-hud] [--export-transparent] [--fov <degrees>] [--near <distance>] \
+                     [--far <distance>] [--precision <decimals>] \
+                     [--scale-format <percent|ratio>] [--font <mono|serif|ttf-file>] [--font-size <pixels>] \
+                     [--font-color <#rrggbb[aa]>] [--script <script-file>] [--remote <port>] \
+                     [--light-preset <classic|studio|headlight|top>] [--no-shadows] <obj-file-name>
+       glium-obj [options] --session <session-file> [<obj-file-name>]
+       glium-obj turntable [--frames <n>] [--size <width>x<height>] [--elevation <degrees>] \
+                     [--delay <ms>] [--out <dir-or-gif>] [--export-transparent] [--script <script-file>] \
+                     [--light-preset <classic|studio|headlight|top>] [--no-shadows] <obj-file-name>
+       glium-obj thumbnails [--size <width>x<height>] [--elevation <degrees>] [--out <dir>] \
+                     [--export-transparent] [--light-preset <classic|studio|headlight|top>] [--no-shadows] <dir>";
+
+/// The things, the application can do.
+pub enum Command {
+    /// Shows the object in a window.
+    View,
+    /// Renders a turntable animation of the object without a window.
+    Turntable(TurntableSettings),
+    /// Renders a thumbnail for each obj file in a directory tree.
+    Thumbnails(ThumbnailSettings),
+}
+
+/// The options provided on the command line.
+pub struct Options {
+    pub command: Command,
+    /// The obj file or, for the thumbnails, the directory with the obj files. The viewer may take
+    /// the obj file from the session instead.
+    pub input: Option<String>,
+    /// The session file, which is restored and saved by the viewer.
+    pub session: Option<PathBuf>,
+    /// The script file, whose commands are executed after the object is loaded.
+    pub script: Option<PathBuf>,
+    /// The port on localhost, where the viewer listens for remote control requests.
+    pub remote: Option<u16>,
+    /// The lights, which replace the lights of the config file.
+    pub light_preset: Option<LightPreset>,
+    /// False, if the key light casts no shadows, e.g. because the software renderer is too slow.
+    pub shadows: bool,
+    /// The units, if they are given. Otherwise they come from the session or default to meter.
+    pub model_unit: Option<Unit>,
+    pub unit: Option<Unit>,
+    pub export: ExportSettings,
+    pub lens: LensOptions,
+    pub hud_format: HudFormat,
+    pub hud_style: HudStyle,
+}
+impl Options {
+    /// Parses the command line arguments, without the name of the program.
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - The arguments of the command line.
+    pub fn parse<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut args = args.peekable();
+        let mut command = Command::View;
+        match args.peek().map(String::as_str) {
+            Some("turntable") => command = Command::Turntable(TurntableSettings::default()),
+            Some("thumbnails") => command = Command::Thumbnails(ThumbnailSettings::default()),
+            _ => (),
+        }
+        if !matches!(command, Command::View) {
+            args.next();
+        }
+
+        let mut input = None;
+        let mut model_unit = None;
+        let mut unit = None;
+        let mut export = ExportSettings::default();
+        let mut lens = LensOptions::default();
+        let mut hud_format = HudFormat::default();
+        let mut hud_style = HudStyle::default();
+        let mut session = None;
+        let mut script = None;
+        let mut remote = None;
+        let mut light_preset = None;
+        let mut shadows = true;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--model-unit" => model_unit = Some(value(&arg, args.next())?.parse()?),
+                "--unit" => unit = Some(value(&arg, args.next())?.parse()?),
+                "--export-multiple" => match number(&arg, args.next())? {
+                    multiple if multiple <= MAX_EXPORT_MULTIPLE => export.multiple = multiple,
+                    multiple => return Err(format!("The export multiple must not exceed {MAX_EXPORT_MULTIPLE}, not '{multiple}'")),
+                },
+                "--export-without-hud" => export.hud = false,
+                "--export-transparent" => export.transparent = true,
+                "--fov" => lens.field_of_view = Some(angle(&arg, args.next())?.to_radians()),
+                "--near" => lens.near = Some(distance(&arg, args.next())?),
+                "--far" => lens.far = Some(distance(&arg, args.next())?),
+                "--precision" => hud_format.precision = decimals(&arg, args.next())?,
+                "--scale-format" => hud_format.scale_style = value(&arg, args.next())?.parse()?,
+                "--font" => hud_style.font = value(&arg, args.next())?.parse()?,
+                "--font-size" => hud_style.font_size = distance(&arg, args.next())?,
+                "--font-color" => hud_style.color = color(&arg, args.next())?,
+                "--light-preset" => light_preset = Some(value(&arg, args.next())?.parse()?),
+                "--no-shadows" => shadows = false,
+                "--session" => match command {
+                    Command::View => session = Some(PathBuf::from(value(&arg, args.next())?)),
+                    _ => return Err(format!("The option '{arg}' is only known for the viewer\n{USAGE}")),
+                },
+                "--script" => match command {
+                    Command::Thumbnails(_) => return Err(format!("The option '{arg}' is not known for thumbnails\n{USAGE}")),
+                    _ => script = Some(PathBuf::from(value(&arg, args.next())?)),
+                },
+                "--remote" => match command {
+                    Command::View => remote = Some(port(&arg, args.next())?),
+                    _ => return Err(format!("The option '{arg}' is only known for the viewer\n{USAGE}")),
+                },
+                "--frames" | "--delay" => match &mut command {
+                    Command::Turntable(settings) => turntable_option(settings, &arg, args.next())?,
+                    _ => return Err(format!("The option '{arg}' is only known for turntables\n{USAGE}")),
+                },
+                "--size" | "--elevation" | "--out" => match &mut command {
+                    Command::Turntable(settings) => turntable_option(settings, &arg, args.next())?,
+                    Command::Thumbnails(settings) => thumbnail_option(settings, &arg, args.next())?,
+                    Command::View => return Err(format!("The option '{arg}' is not known for the viewer\n{USAGE}")),
+                },
+                _ if arg.starts_with("--") => return Err(format!("Unknown option '{arg}'\n{USAGE}")),
+                _ => input = Some(arg),
+            }
+        }
+
+        if input.is_none() && session.is_none() {
+            return Err(match command {
+                Command::Thumbnails(_) => format!("No directory provided!\n{USAGE}"),
+                _ => format!("No object file provided!\n{USAGE}"),
+            });
+        }
+        lens.apply(&mut Lens::default())?;
+        Ok(Self { command, input, session, script, remote, light_preset, shadows, model_unit, unit, export, lens, hud_format, hud_style })
+    }
+}
+
+/// The settings of the lens, which are given on the command line. They win over the lens of the
+/// session.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LensOptions {
+    /// The vertical field of view in RAD.
+    pub field_of_view: Option<f32>,
+    pub near: Option<f32>,
+    pub far: Option<f32>,
+}
+impl LensOptions {
+    /// Replaces the settings of the lens, which are given. A given plane turns off the fitting of
+    /// the planes to the scene.
+    ///
+    /// # Arguments
+    ///
+    /// * 'lens' - The lens of the defaults or of the session.
+    pub fn apply(&self, lens: &mut Lens) -> Result<(), String> {
+        if let Some(field_of_view) = self.field_of_view {
+            lens.set_field_of_view(field_of_view);
+        }
+        if let Some(near) = self.near {
+            lens.near = near;
+            lens.auto_clip = false;
+        }
+        if let Some(far) = self.far {
+            lens.far = far;
+            lens.auto_clip = false;
+        }
+        if !lens.auto_clip && lens.near >= lens.far {
+            return Err(format!("The near plane ({}) must be closer than the far plane ({})", lens.near, lens.far));
+        }
+        Ok(())
+    }
+}
+
+/// Makes sure, that an option is followed by its value.
+fn value(option: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("The option '{option}' needs a value\n{USAGE}"))
+}
+
+/// Makes sure, that an option is followed by a positive number.
+fn number(option: &str, arg: Option<String>) -> Result<u32, String> {
+    let value = value(option, arg)?;
+    match value.parse() {
+        Ok(number) if number > 0 => Ok(number),
+        _ => Err(format!("The option '{option}' needs a positive number, not '{value}'")),
+    }
+}
+
+/// Makes sure, that an option is followed by a port number.
+fn port(option: &str, arg: Option<String>) -> Result<u16, String> {
+    let value = value(option, arg)?;
+    match value.parse() {
+        Ok(port) if port > 0 => Ok(port),
+        _ => Err(format!("The option '{option}' needs a port from 1 to 65535, not '{value}'")),
+    }
+}
+
+/// Makes sure, that an option is followed by a sensible number of decimals.
+fn decimals(option: &str, arg: Option<String>) -> Result<usize, String> {
+    let value = value(option, arg)?;
+    match value.parse() {
+        Ok(decimals) if decimals <= MAX_DECIMALS => Ok(decimals),
+        _ => Err(format!("The option '{option}' needs a number of decimals from 0 to {MAX_DECIMALS}, not '{value}'")),
+    }
+}
+
+/// Sets one of the turntable settings.
+fn turntable_option(settings: &mut TurntableSettings, option: &str, arg: Option<String>) -> Result<(), String> {
+    match option {
+        "--frames" => settings.frames = number(option, arg)?,
+        "--delay" => settings.delay = number(option, arg)?,
+        "--out" => settings.out = PathBuf::from(value(option, arg)?),
+        "--elevation" => settings.elevation = angle(option, arg)?,
+        _ => (settings.width, settings.height) = size(option, arg)?,
+    }
+    Ok(())
+}
+
+/// Sets one of the thumbnail settings.
+fn thumbnail_option(settings: &mut ThumbnailSettings, option: &str, arg: Option<String>) -> Result<(), String> {
+    match option {
+        "--out" => settings.out = PathBuf::from(value(option, arg)?),
+        "--elevation" => settings.elevation = angle(option, arg)?,
+        _ => (settings.width, settings.height) = size(option, arg)?,
+    }
+    Ok(())
+}
+
+/// Makes sure, that an option is followed by an angle in degrees.
+fn angle(option: &str, arg: Option<String>) -> Result<f32, String> {
+    let value = value(option, arg)?;
+    value.parse().map_err(|_| format!("The option '{option}' needs an angle in degrees, not '{value}'"))
+}
+
+/// Makes sure, that an option is followed by a positive distance.
+fn distance(option: &str, arg: Option<String>) -> Result<f32, String> {
+    let value = value(option, arg)?;
+    match value.parse::<f32>() {
+        Ok(distance) if distance > 0.0 && distance.is_finite() => Ok(distance),
+        _ => Err(format!("The option '{option}' needs a positive distance, not '{value}'")),
+    }
+}
+
+/// Makes sure, that an option is followed by a color like #ffcc00 or #ffcc0080.
+fn color(option: &str, arg: Option<String>) -> Result<[f32; 4], String> {
+    let value = value(option, arg)?;
+    let hex = value.strip_prefix('#').unwrap_or(&value);
+    let channels: Option<Vec<f32>> = (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|channel| u8::from_str_radix(channel, 16).ok()))
+        .map(|channel| channel.map(|channel| channel as f32 / 255.0))
+        .collect();
+    match channels.as_deref() {
+        Some(&[r, g, b]) => Ok([r, g, b, 1.0]),
+        Some(&[r, g, b, a]) => Ok([r, g, b, a]),
+        _ => Err(format!("The option '{option}' needs a color like #ffcc00 or #ffcc0080, not '{value}'")),
+    }
+}
+
+/// Makes sure, that an option is followed by a size like 640x480.
+fn size(option: &str, arg: Option<String>) -> Result<(u32, u32), String> {
+    let value = value(option, arg)?;
+    value.split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+        .filter(|&(width, height)| width > 0 && height > 0)
+        .ok_or_else(|| format!("The option '{option}' needs a size like 640x480, not '{value}'"))
+}
@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use glium::Display;
+use glium::draw_parameters::TimeElapsedQuery;
+
+/// The number of frames, which are shown in the frame time graph.
+pub const GRAPH_FRAMES: usize = 120;
+
+/// The draw calls of one frame and the triangles drawn by them.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DrawStats {
+    pub draw_calls: usize,
+    pub triangles: usize,
+}
+impl DrawStats {
+    /// Counts a draw call.
+    ///
+    /// # Arguments
+    ///
+    /// * 'triangles' - The triangles drawn by the call. Lines and points count as none.
+    pub fn count(&mut self, triangles: usize) {
+        self.draw_calls += 1;
+        self.triangles += triangles;
+    }
+}
+
+/// Measures the frames, which are drawn into the window. The GPU time is measured with a timer
+/// query around the draw call of the object only, so it leaves out the shadow pass, the grid, the
+/// highlights and the HUD. The result of the query is only read, when it is ready, so the
+/// measurement never stalls the pipeline.
+pub struct Performance {
+    // The times between the last frames in seconds, the latest at the back.
+    frame_times: VecDeque<f32>,
+    last_frame: Option<Instant>,
+    frame_start: Instant,
+    /// The time the CPU needed to render the last frame.
+    pub cpu_time: Duration,
+    /// The time the GPU needed to draw the object, if the driver supports timer queries.
+    pub gpu_time: Option<Duration>,
+    pub draw: DrawStats,
+    active: Option<TimeElapsedQuery>,
+    pending: Option<TimeElapsedQuery>,
+    supported: bool,
+}
+impl Default for Performance {
+    fn default() -> Self {
+        Self {
+            frame_times: VecDeque::with_capacity(GRAPH_FRAMES),
+            last_frame: None,
+            frame_start: Instant::now(),
+            cpu_time: Duration::ZERO,
+            gpu_time: None,
+            draw: DrawStats::default(),
+            active: None,
+            pending: None,
+            supported: true,
+        }
+    }
+}
+impl Performance {
+    /// Starts the measurement of a frame. A new timer query is started, when the query of an
+    /// earlier frame has delivered its result.
+    pub fn begin_frame(&mut self, display: &Display) {
+        let now = Instant::now();
+        if let Some(last_frame) = self.last_frame {
+            if self.frame_times.len() == GRAPH_FRAMES {
+                self.frame_times.pop_front();
+            }
+            self.frame_times.push_back((now - last_frame).as_secs_f32());
+        }
+        self.last_frame = Some(now);
+        self.frame_start = now;
+
+        if self.pending.as_ref().is_some_and(TimeElapsedQuery::is_ready) {
+            let nanoseconds = self.pending.take().unwrap().get();
+            self.gpu_time = Some(Duration::from_nanos(nanoseconds as u64));
+        }
+        if self.pending.is_none() && self.supported {
+            match TimeElapsedQuery::new(display) {
+                Ok(query) => self.active = Some(query),
+                Err(error) => {
+                    log::info!("[Performance::begin_frame()] No GPU timing: {error:?}");
+                    self.supported = false;
+                }
+            }
+        }
+    }
+
+    /// The timer query of the current frame, if there is one.
+    pub fn query(&self) -> Option<&TimeElapsedQuery> {
+        self.active.as_ref()
+    }
+
+    /// Ends the measurement of a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * 'draw' - The draw calls of the frame.
+    pub fn end_frame(&mut self, draw: DrawStats) {
+        self.cpu_time = self.frame_start.elapsed();
+        self.draw = draw;
+        if let Some(query) = self.active.take() {
+            self.pending = Some(query);
+        }
+    }
+
+    /// The frames per second, averaged over the frames of the graph. This is only the frame rate,
+    /// while the frames are drawn continuously.
+    pub fn fps(&self) -> f32 {
+        let total: f32 = self.frame_times.iter().sum();
+        if total > 0.0 { self.frame_times.len() as f32 / total } else { 0.0 }
+    }
+
+    /// The times between the last frames in seconds, the latest at the back.
+    pub fn frame_times(&self) -> &VecDeque<f32> {
+        &self.frame_times
+    }
+}
@@ -0,0 +1,333 @@
+use nalgebra::{Matrix4, Point3, Vector3, Vector4};
+
+use crate::model::{Model, Vertex};
+use crate::view::{projection_matrix, view_matrix, world_matrix};
+
+// A node of the hierarchy is not split any further, if it holds no more triangles than this.
+const LEAF_SIZE: usize = 4;
+
+/// A ray, given by its origin and its direction.
+#[derive(Copy, Clone, Debug)]
+pub struct Ray {
+    pub origin: Vector3<f32>,
+    pub direction: Vector3<f32>,
+}
+
+/// The result of picking the object: The triangle under the cursor and the point, where it was
+/// hit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pick {
+    pub triangle: usize,
+    pub vertices: [usize; 3],
+    pub group: Option<String>,
+    /// The hit point in object coordinates. It stays on the surface, when the object is moved.
+    pub local_position: Vector3<f32>,
+    pub position: Vector3<f32>,
+    pub normal: Vector3<f32>,
+}
+
+/// A node of the bounding volume hierarchy. Inner nodes refer to their two children, leaves to a
+/// range of the triangle list.
+#[derive(Clone, Debug)]
+struct Node {
+    min: Vector3<f32>,
+    max: Vector3<f32>,
+    kind: NodeKind,
+}
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+    Inner { left: usize, right: usize },
+    Leaf { start: usize, end: usize },
+}
+
+/// A bounding volume hierarchy over the triangles of the object in object coordinates. As the
+/// object coordinates never change, the hierarchy is built only once, after the object has been
+/// loaded. Rays are transformed into the object coordinates instead.
+pub struct Bvh {
+    nodes: Vec<Node>,
+    triangles: Vec<usize>,
+}
+impl Bvh {
+    /// Builds the hierarchy by splitting the triangles at the median of their centroids along the
+    /// longest axis of each node.
+    ///
+    /// # Arguments
+    ///
+    /// * 'vertices' - The vertices of the object.
+    /// * 'indices' - The vertex indices of the triangles, three for each triangle.
+    pub fn new(vertices: &[Vertex], indices: &[u16]) -> Self {
+        let mut bvh = Self { nodes: Vec::new(), triangles: (0..indices.len() / 3).collect() };
+        let centroids: Vec<Vector3<f32>> = bvh.triangles.iter()
+            .map(|&t| {
+                let [a, b, c] = triangle(vertices, indices, t);
+                (a + b + c) / 3.0
+            })
+            .collect();
+        if !bvh.triangles.is_empty() {
+            let count = bvh.triangles.len();
+            bvh.build(vertices, indices, &centroids, 0, count);
+        }
+        bvh
+    }
+
+    /// Builds the node for the triangles in the range start..end and returns its index.
+    fn build(&mut self, vertices: &[Vertex], indices: &[u16], centroids: &[Vector3<f32>],
+             start: usize, end: usize) -> usize {
+        let mut min = Vector3::repeat(f32::MAX);
+        let mut max = Vector3::repeat(f32::MIN);
+        for &t in &self.triangles[start..end] {
+            for p in triangle(vertices, indices, t) {
+                min = min.inf(&p);
+                max = max.sup(&p);
+            }
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(Node { min, max, kind: NodeKind::Leaf { start, end } });
+        if end - start <= LEAF_SIZE {
+            return index;
+        }
+
+        let axis = (max - min).imax();
+        let middle = (start + end) / 2;
+        self.triangles[start..end].select_nth_unstable_by(middle - start, |&a, &b| {
+            centroids[a][axis].total_cmp(&centroids[b][axis])
+        });
+        let left = self.build(vertices, indices, centroids, start, middle);
+        let right = self.build(vertices, indices, centroids, middle, end);
+        self.nodes[index].kind = NodeKind::Inner { left, right };
+        index
+    }
+
+    /// Finds the nearest triangle hit by the ray. The result is the index of the triangle, the
+    /// distance along the ray and the barycentric coordinates of the hit point.
+    ///
+    /// # Arguments
+    ///
+    /// * 'vertices' - The vertices of the object, the hierarchy was built from.
+    /// * 'indices' - The vertex indices of the triangles.
+    /// * 'ray' - The ray in object coordinates.
+    pub fn intersect(&self, vertices: &[Vertex], indices: &[u16], ray: &Ray) -> Option<(usize, f32, f32, f32)> {
+        let mut nearest: Option<(usize, f32, f32, f32)> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let mut limit = nearest.map_or(f32::MAX, |hit| hit.1);
+            if !hits_box(ray, &node.min, &node.max, limit) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Inner { left, right } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                NodeKind::Leaf { start, end } => {
+                    for &t in &self.triangles[start..end] {
+                        if let Some((distance, u, v)) = hits_triangle(ray, &triangle(vertices, indices, t)) {
+                            if distance < limit {
+                                nearest = Some((t, distance, u, v));
+                                limit = distance;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        nearest
+    }
+}
+
+/// Calculates the ray from the viewer through the provided position on the screen in world
+/// coordinates. It is the inverse of the projection, which is used to draw the object.
+///
+/// # Arguments
+///
+/// * 'model' - The model, which provides the position and direction of the viewer.
+/// * 'x', 'y' - The position on the screen in pixels, starting at the top left corner.
+/// * 'width', 'height' - The size of the screen in pixels.
+pub fn cursor_ray(model: &Model, x: f32, y: f32, width: u32, height: u32) -> Option<Ray> {
+    let view: Matrix4<f32> = view_matrix(&model.view_position, &model.view_direction, &model.up).into();
+    let projection: Matrix4<f32> = projection_matrix(model, width, height).into();
+    let inverse = (projection * view).try_inverse()?;
+
+    let ndc_x = 2.0 * x / width as f32 - 1.0;
+    let ndc_y = 1.0 - 2.0 * y / height as f32;
+    let near = inverse * Vector4::new(ndc_x, ndc_y, -1.0, 1.0);
+    let far = inverse * Vector4::new(ndc_x, ndc_y, 1.0, 1.0);
+    let near = near.xyz() / near[3];
+    let far = far.xyz() / far[3];
+    Some(Ray { origin: near, direction: (far - near).normalize() })
+}
+
+/// Picks the object at the provided position on the screen.
+///
+/// # Arguments
+///
+/// * 'model' - The model with the object to pick.
+/// * 'x', 'y' - The position on the screen in pixels, starting at the top left corner.
+/// * 'width', 'height' - The size of the screen in pixels.
+pub fn pick_at(model: &Model, x: f32, y: f32, width: u32, height: u32) -> Option<Pick> {
+    let ray = cursor_ray(model, x, y, width, height)?;
+    pick(model, &ray)
+}
+
+/// Picks the object with a ray in world coordinates.
+///
+/// # Arguments
+///
+/// * 'model' - The model with the object to pick.
+/// * 'ray' - The ray in world coordinates.
+pub fn pick(model: &Model, ray: &Ray) -> Option<Pick> {
+    let world = world_matrix(model);
+    let inverse = world.try_inverse()?;
+    let origin = inverse.transform_point(&Point3::from(ray.origin)).coords;
+    let direction = inverse.transform_vector(&ray.direction);
+    let local_ray = Ray { origin, direction };
+
+    let vertices = &model.object.vertices;
+    let indices = &model.object.indices;
+    let (t, _, u, v) = model.bvh.intersect(vertices, indices, &local_ray)?;
+
+    let corner_indices = [0, 1, 2].map(|i| indices[3 * t + i] as usize);
+    let [a, b, c] = triangle(vertices, indices, t);
+    let local_position = a * (1.0 - u - v) + b * u + c * v;
+    let [na, nb, nc] = corner_indices.map(|i| Vector3::from(vertices[i].normal));
+    let local_normal = na * (1.0 - u - v) + nb * u + nc * v;
+
+    // The object is only scaled uniformly, so the normals can be transformed like directions.
+    let position = world.transform_point(&Point3::from(local_position)).coords;
+    let normal = world.transform_vector(&local_normal).normalize();
+
+    Some(Pick {
+        triangle: t,
+        vertices: corner_indices,
+        group: model.group_of(t).map(|name| name.to_string()),
+        local_position,
+        position,
+        normal,
+    })
+}
+
+/// The three corners of a triangle in object coordinates.
+fn triangle(vertices: &[Vertex], indices: &[u16], t: usize) -> [Vector3<f32>; 3] {
+    [0, 1, 2].map(|i| Vector3::from(vertices[indices[3 * t + i] as usize].position))
+}
+
+/// The slab test of a ray against an axis-aligned box.
+fn hits_box(ray: &Ray, min: &Vector3<f32>, max: &Vector3<f32>, limit: f32) -> bool {
+    let mut t_min = 0.0f32;
+    let mut t_max = limit;
+    for axis in 0..3 {
+        let inv = 1.0 / ray.direction[axis];
+        let mut t0 = (min[axis] - ray.origin[axis]) * inv;
+        let mut t1 = (max[axis] - ray.origin[axis]) * inv;
+        if inv < 0.0 {
+            std::mem::swap(&mut t0, &mut t1);
+        }
+        t_min = t_min.max(t0);
+        t_max = t_max.min(t1);
+        if t_max < t_min {
+            return false;
+        }
+    }
+    true
+}
+
+/// The Möller-Trumbore intersection of a ray and a triangle. Both sides of the triangle are hit.
+/// The result is the distance along the ray and the barycentric coordinates of the hit point.
+fn hits_triangle(ray: &Ray, corners: &[Vector3<f32>; 3]) -> Option<(f32, f32, f32)> {
+    let edge1 = corners[1] - corners[0];
+    let edge2 = corners[2] - corners[0];
+    let p = ray.direction.cross(&edge2);
+    let determinant = edge1.dot(&p);
+    if determinant.abs() < f32::EPSILON {
+        return None;
+    }
+    let inv = 1.0 / determinant;
+    let s = ray.origin - corners[0];
+    let u = s.dot(&p) * inv;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(&edge1);
+    let v = ray.direction.dot(&q) * inv;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = edge2.dot(&q) * inv;
+    if distance > 0.0 { Some((distance, u, v)) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ray(origin: [f32; 3], direction: [f32; 3]) -> Ray {
+        Ray { origin: Vector3::from(origin), direction: Vector3::from(direction) }
+    }
+
+    fn unit_triangle() -> [Vector3<f32>; 3] {
+        [Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)]
+    }
+
+    #[test]
+    fn hits_a_triangle_from_both_sides() {
+        let (distance, u, v) = hits_triangle(&ray([0.25, 0.5, 2.0], [0.0, 0.0, -1.0]), &unit_triangle()).unwrap();
+        assert!((distance - 2.0).abs() < 1e-6);
+        assert!((u - 0.25).abs() < 1e-6 && (v - 0.5).abs() < 1e-6);
+        let (distance, _, _) = hits_triangle(&ray([0.25, 0.5, -3.0], [0.0, 0.0, 1.0]), &unit_triangle()).unwrap();
+        assert!((distance - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn misses_a_triangle_beside_or_behind_the_ray() {
+        assert_eq!(hits_triangle(&ray([0.8, 0.8, 2.0], [0.0, 0.0, -1.0]), &unit_triangle()), None);
+        assert_eq!(hits_triangle(&ray([-0.1, 0.5, 2.0], [0.0, 0.0, -1.0]), &unit_triangle()), None);
+        assert_eq!(hits_triangle(&ray([0.25, 0.25, 2.0], [0.0, 0.0, 1.0]), &unit_triangle()), None);
+    }
+
+    #[test]
+    fn misses_a_triangle_parallel_to_the_ray() {
+        assert_eq!(hits_triangle(&ray([-1.0, 0.25, 0.0], [1.0, 0.0, 0.0]), &unit_triangle()), None);
+        assert_eq!(hits_triangle(&ray([-1.0, 0.25, 0.5], [1.0, 0.0, 0.0]), &unit_triangle()), None);
+    }
+
+    #[test]
+    fn finds_the_same_nearest_hit_as_testing_all_triangles() {
+        // A simple generator, so the test always uses the same triangles and rays.
+        let mut seed = 12345u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0
+        };
+        let mut vertices = Vec::new();
+        for _ in 0..200 {
+            let center = [random() * 4.0, random() * 4.0, random() * 4.0];
+            for _ in 0..3 {
+                let position = [center[0] + random(), center[1] + random(), center[2] + random()];
+                vertices.push(Vertex { position, normal: [0.0, 0.0, 1.0] });
+            }
+        }
+        let indices: Vec<u16> = (0..vertices.len() as u16).collect();
+        let bvh = Bvh::new(&vertices, &indices);
+
+        let mut hits = 0;
+        for _ in 0..500 {
+            // The rays start around the triangles and point into them, so most of them hit.
+            let origin = Vector3::new(random(), random(), random()) * 8.0;
+            let target = Vector3::new(random(), random(), random()) * 4.0;
+            let ray = Ray { origin, direction: (target - origin).normalize() };
+            let expected = (0..indices.len() / 3)
+                .filter_map(|t| hits_triangle(&ray, &triangle(&vertices, &indices, t)).map(|(distance, _, _)| (t, distance)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            let found = bvh.intersect(&vertices, &indices, &ray).map(|(t, distance, _, _)| (t, distance));
+            assert_eq!(found, expected);
+            hits += found.is_some() as usize;
+        }
+        assert!(hits > 250, "Only {hits} rays hit a triangle");
+    }
+}
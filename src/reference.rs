@@ -0,0 +1,184 @@
+use glium::{Display, Program, Surface, VertexBuffer};
+use glium::index::PrimitiveType;
+
+use crate::hud::FONT_SIZE;
+use crate::model::Model;
+use crate::performance::DrawStats;
+use crate::view::{Viewport, OBJECT_HOME, depth_test, view_matrix};
+
+const GRID_COLOR: [f32; 3] = [0.6, 0.6, 0.7];
+const GRID_MAJOR_COLOR: [f32; 3] = [0.85, 0.85, 0.9];
+const X_AXIS_COLOR: [f32; 3] = [1.0, 0.2, 0.2];
+const Y_AXIS_COLOR: [f32; 3] = [0.2, 1.0, 0.2];
+// Pure blue would vanish in front of the blue background.
+const Z_AXIS_COLOR: [f32; 3] = [0.6, 0.85, 1.0];
+
+/// The length of the world axes relative to the extent of the object.
+pub const AXES_LENGTH: f32 = 1.5;
+
+// Every n-th line of the grid is drawn as a major line.
+const GRID_MAJOR_EVERY: i32 = 5;
+
+// The edge length of the orientation gizmo in pixels and its distance to the window border.
+const GIZMO_SIZE: f32 = 90.0;
+const GIZMO_MARGIN: f32 = 10.0;
+
+/// A colored vertex of the reference geometry. All reference geometry is drawn as lines.
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+implement_vertex!(Vertex, position, color);
+
+/// The reference geometry of the scene: A ground grid below the object, the axes of the world
+/// coordinate system and a small gizmo in the lower left corner, showing the orientation of the
+/// world axes as seen from the camera.
+pub struct ReferenceView {
+    program: Program,
+    grid: VertexBuffer<Vertex>,
+    grid_extent: f32,
+    axes: VertexBuffer<Vertex>,
+    axes_extent: f32,
+    gizmo: VertexBuffer<Vertex>,
+}
+impl ReferenceView {
+    pub fn new(display: &Display, model: &Model) -> Self {
+        let vertex_shader_src = include_str!("reference.vertex.glsl");
+        let fragment_shader_src = include_str!("reference.fragment.glsl");
+        let program = Program::from_source(display, vertex_shader_src, fragment_shader_src,
+                                           None).unwrap();
+
+        let grid_extent = model.world_extent();
+        let grid = VertexBuffer::new(display, &grid_vertices(grid_extent)).unwrap();
+        let axes_extent = model.world_extent();
+        let axes = VertexBuffer::new(display, &axes_vertices(AXES_LENGTH * axes_extent)).unwrap();
+        let gizmo = VertexBuffer::new(display, &axes_vertices(0.8)).unwrap();
+
+        Self { program, grid, grid_extent, axes, axes_extent, gizmo }
+    }
+
+    /// Draws the enabled reference geometry into the scene. Grid and axes share the depth buffer
+    /// with the object, the gizmo is drawn on top of everything else.
+    pub fn draw<S: Surface>(&mut self, display: &Display, target: &mut S, model: &Model, viewport: &Viewport,
+                            stats: &mut DrawStats) {
+        self.update_geometry(display, model);
+
+        let view = view_matrix(&model.view_position, &model.view_direction, &model.up);
+        let perspective = viewport.projection(model);
+
+        let params = glium::DrawParameters {
+            depth: depth_test(),
+            .. Default::default()
+        };
+        let uniforms = uniform! { view: view, perspective: perspective };
+        let lines = glium::index::NoIndices(PrimitiveType::LinesList);
+
+        if model.show_grid {
+            target.draw(&self.grid, lines, &self.program, &uniforms, &params).unwrap();
+            stats.count(0);
+        }
+        if model.show_axes {
+            target.draw(&self.axes, lines, &self.program, &uniforms, &params).unwrap();
+            stats.count(0);
+        }
+        if model.show_gizmo {
+            self.draw_gizmo(target, model, viewport, stats);
+        }
+    }
+
+    /// The gizmo uses only the rotation of the camera and an orthographic projection, which puts
+    /// it at the same size into the lower left corner of the picture.
+    fn draw_gizmo<S: Surface>(&self, target: &mut S, model: &Model, viewport: &Viewport, stats: &mut DrawStats) {
+        let view = view_matrix(&[0.0, 0.0, 0.0], &model.view_direction, &model.up);
+
+        let (width, height) = (viewport.width as f32, viewport.height as f32);
+        let size = (GIZMO_SIZE * viewport.scale).min(width).min(height);
+        let margin = GIZMO_MARGIN * viewport.scale;
+        let center_x = margin + size / 2.0;
+        let center_y = margin + 2.0 * FONT_SIZE * viewport.scale + size / 2.0;
+        let corner: [[f32; 4]; 4] = [
+            [size / width, 0.0, 0.0, 0.0],
+            [0.0, size / height, 0.0, 0.0],
+            [0.0, 0.0, 0.5, 0.0],
+            [2.0 * center_x / width - 1.0, 2.0 * center_y / height - 1.0, 0.0, 1.0],
+        ];
+
+        target.draw(&self.gizmo,
+                    glium::index::NoIndices(PrimitiveType::LinesList),
+                    &self.program,
+                    &uniform! { view: view, perspective: viewport.restrict(corner) },
+                    &Default::default()).unwrap();
+        stats.count(0);
+    }
+
+    /// The grid and the axes grow and shrink with the model. The buffers are only rebuilt, when
+    /// the size of the model has really changed.
+    fn update_geometry(&mut self, display: &Display, model: &Model) {
+        let extent = model.world_extent();
+        if model.show_grid && extent != self.grid_extent {
+            self.grid = VertexBuffer::new(display, &grid_vertices(extent)).unwrap();
+            self.grid_extent = extent;
+        }
+        if model.show_axes && extent != self.axes_extent {
+            self.axes = VertexBuffer::new(display, &axes_vertices(AXES_LENGTH * extent)).unwrap();
+            self.axes_extent = extent;
+        }
+    }
+}
+
+/// Creates the lines of the ground grid. The grid lies right below the home position of the
+/// object and covers the object and the origin of the world. The spacing of the lines is a power
+/// of ten, chosen by the size of the object.
+///
+/// # Arguments
+///
+/// * 'extent' - The extent of the object in world coordinates.
+fn grid_vertices(extent: f32) -> Vec<Vertex> {
+    let (step, count) = grid_layout(extent, OBJECT_HOME[2].abs());
+    let half = step * count as f32;
+    let y = OBJECT_HOME[1] - extent;
+    let (cx, cz) = (OBJECT_HOME[0], OBJECT_HOME[2]);
+
+    let mut vertices = Vec::new();
+    for i in -count..=count {
+        let color = if i % GRID_MAJOR_EVERY == 0 { GRID_MAJOR_COLOR } else { GRID_COLOR };
+        let d = i as f32 * step;
+        vertices.push(Vertex { position: [cx + d, y, cz - half], color });
+        vertices.push(Vertex { position: [cx + d, y, cz + half], color });
+        vertices.push(Vertex { position: [cx - half, y, cz + d], color });
+        vertices.push(Vertex { position: [cx + half, y, cz + d], color });
+    }
+    vertices
+}
+
+/// Calculates the spacing of the grid lines and the number of lines on each side of the center.
+///
+/// # Arguments
+///
+/// * 'extent' - The extent of the object in world coordinates.
+/// * 'distance' - The distance between the object and the origin, which must be covered as well.
+fn grid_layout(extent: f32, distance: f32) -> (f32, i32) {
+    let extent = if extent.is_finite() && extent > 0.0 { extent } else { 1.0 };
+    let step = 10.0f32.powf(extent.log10().floor()) / 2.0;
+    let half = 2.0 * extent + distance;
+    let count = (half / step).ceil() as i32;
+    (step, count.clamp(1, 200))
+}
+
+/// Creates the three world axes, starting at the origin.
+///
+/// # Arguments
+///
+/// * 'length' - The length of each axis.
+fn axes_vertices(length: f32) -> Vec<Vertex> {
+    let origin = [0.0, 0.0, 0.0];
+    vec![
+        Vertex { position: origin, color: X_AXIS_COLOR },
+        Vertex { position: [length, 0.0, 0.0], color: X_AXIS_COLOR },
+        Vertex { position: origin, color: Y_AXIS_COLOR },
+        Vertex { position: [0.0, length, 0.0], color: Y_AXIS_COLOR },
+        Vertex { position: origin, color: Z_AXIS_COLOR },
+        Vertex { position: [0.0, 0.0, length], color: Z_AXIS_COLOR },
+    ]
+}
@@ -0,0 +1,15 @@
+#version 150
+
+in vec3 position;
+in vec3 color;
+
+out vec3 v_color;
+
+uniform mat4 perspective;
+uniform mat4 view;
+
+// The reference geometry is already given in world coordinates.
+void main() {
+    v_color = color;
+    gl_Position = perspective * view * vec4(position, 1.0);
+}
@@ -0,0 +1,474 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::model::Model;
+use crate::picking::Pick;
+
+// The largest body of a request in bytes. The commands of a request are short, so anything
+// larger is a mistake.
+const MAX_BODY: usize = 1 << 20;
+
+// The largest request line plus headers in bytes.
+const MAX_HEAD: usize = 16 << 10;
+
+// The connections, which are served at the same time. Further clients are turned away.
+const MAX_CONNECTIONS: usize = 16;
+
+// The time, a client may take to send the next part of its request or to take the response.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A request of a client of the control server. It is sent into the event loop, where it is
+/// applied to the model, and answered from there.
+#[derive(Debug)]
+pub struct RemoteRequest {
+    pub method: String,
+    /// The path of the URL without the query.
+    pub path: String,
+    /// The parameters of the query, like x=10 in /pick?x=10&y=20.
+    pub query: Vec<(String, String)>,
+    /// The headers with their names in lower case.
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    reply: Sender<Response>,
+}
+impl RemoteRequest {
+    /// The value of a parameter of the query.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// The value of a header. The name is given in lower case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Sends the response back to the client. A client, which has gone, is ignored.
+    pub fn reply(&self, response: Response) {
+        let _ = self.reply.send(response);
+    }
+}
+
+/// The response to a request. The body is a JSON object, which always contains "ok".
+#[derive(Clone, Debug)]
+pub struct Response {
+    status: u16,
+    body: String,
+}
+impl Response {
+    /// A successful response.
+    ///
+    /// # Arguments
+    ///
+    /// * 'fields' - The fields besides "ok", as a struct, which becomes a JSON object.
+    pub fn ok<T: Serialize>(fields: &T) -> Self {
+        match serde_json::to_string(&Body { ok: true, fields }) {
+            Ok(body) => Self { status: 200, body },
+            Err(error) => Self::error(500, &format!("Cannot encode the response: {error}")),
+        }
+    }
+
+    /// A failed response with the reason of the failure.
+    ///
+    /// # Arguments
+    ///
+    /// * 'status' - The HTTP status, e.g. 400 for a bad command.
+    /// * 'message' - The reason of the failure.
+    pub fn error(status: u16, message: &str) -> Self {
+        let body = serde_json::to_string(&Body { ok: false, fields: &Failure { error: message } })
+            .expect("A message is always valid JSON");
+        Self { status, body }
+    }
+}
+
+/// The body of a response: "ok" followed by the fields of the response.
+#[derive(Serialize)]
+struct Body<'a, T: Serialize> {
+    ok: bool,
+    #[serde(flatten)]
+    fields: &'a T,
+}
+
+/// The fields of a failed response.
+#[derive(Serialize)]
+struct Failure<'a> {
+    error: &'a str,
+}
+
+/// The lines, which the commands of a request printed.
+#[derive(Serialize)]
+pub struct Output {
+    pub output: Vec<String>,
+}
+
+/// The file, which was written by a request.
+#[derive(Serialize)]
+pub struct Written {
+    pub path: String,
+}
+
+/// The picked triangle, which is null, if nothing is picked.
+#[derive(Serialize)]
+pub struct Picked<'a> {
+    pub pick: Option<PickState<'a>>,
+}
+impl<'a> Picked<'a> {
+    pub fn of(pick: Option<&'a Pick>) -> Self {
+        Self { pick: pick.map(PickState::of) }
+    }
+}
+
+/// A picked triangle. The position is given in world coordinates.
+#[derive(Serialize)]
+pub struct PickState<'a> {
+    group: Option<&'a str>,
+    triangle: usize,
+    vertices: [usize; 3],
+    position: [f32; 3],
+    normal: [f32; 3],
+}
+impl<'a> PickState<'a> {
+    fn of(pick: &'a Pick) -> Self {
+        Self { group: pick.group.as_deref(), triangle: pick.triangle, vertices: pick.vertices,
+               position: pick.position.into(), normal: pick.normal.into() }
+    }
+}
+
+/// The state of the viewer: The file, the object transform, the camera and the picked triangle.
+/// Numbers, which are not finite, become null, as JSON knows no infinity.
+#[derive(Serialize)]
+pub struct State<'a> {
+    file: &'a str,
+    object: ObjectState,
+    camera: CameraState,
+    pick: Option<PickState<'a>>,
+}
+impl<'a> State<'a> {
+    pub fn of(model: &'a Model) -> Self {
+        Self {
+            file: &model.file_name,
+            object: ObjectState {
+                position: model.object_position.into(),
+                rotation: model.rot.map(f32::to_degrees),
+                scale: model.scaling_factor,
+            },
+            camera: CameraState {
+                position: model.view_position,
+                direction: model.line_of_sight().into(),
+                up: model.up,
+                fov: model.lens.field_of_view.to_degrees(),
+                projection: format!("{:?}", model.projection).to_lowercase(),
+                navigation: format!("{:?}", model.navigation).to_lowercase(),
+            },
+            pick: model.current_pick().map(PickState::of),
+        }
+    }
+}
+
+/// The transform of the object. The rotation is given in degrees.
+#[derive(Serialize)]
+struct ObjectState {
+    position: [f32; 3],
+    rotation: [f32; 3],
+    scale: f32,
+}
+
+/// The camera. The field of view is given in degrees.
+#[derive(Serialize)]
+struct CameraState {
+    position: [f32; 3],
+    direction: [f32; 3],
+    up: [f32; 3],
+    fov: f32,
+    projection: String,
+    navigation: String,
+}
+
+/// The running control server.
+#[derive(Clone, Debug)]
+pub struct Server {
+    /// The port, the server listens on. It differs from the requested port 0.
+    pub port: u16,
+    /// The secret, which each request must carry as "Authorization: Bearer <token>".
+    pub token: String,
+}
+
+/// Starts the control server on a port of localhost. Each connection carries one HTTP request,
+/// which is passed on to the event loop. The server runs in threads of its own, until the
+/// application ends. Requests without the token of the server, from a web page, i.e. with an
+/// Origin header, or for another host are rejected, so web pages cannot drive the viewer.
+///
+/// # Arguments
+///
+/// * 'port' - The port, the server listens on. With 0 the system chooses a free port.
+/// * 'forward' - Passes a request on to the event loop, e.g. by an event loop proxy. Returns
+///   false, if the event loop is gone.
+pub fn start<F>(port: u16, forward: F) -> std::io::Result<Server>
+    where F: Fn(RemoteRequest) -> bool + Clone + Send + 'static {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    let server = Server { port: listener.local_addr()?.port(), token: random_token()? };
+    log::info!("Control server listening on http://127.0.0.1:{}", server.port);
+    let connections = Arc::new(AtomicUsize::new(0));
+    let accepted = server.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                        let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+                        let _ = write_response(&stream, &Response::error(503, "Too many connections"));
+                        continue;
+                    }
+                    let (forward, server, connections) = (forward.clone(), accepted.clone(), connections.clone());
+                    std::thread::spawn(move || {
+                        handle_connection(stream, &server, &forward);
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                    });
+                },
+                Err(error) => log::warn!("[remote::start()] Cannot accept a connection: {error}"),
+            }
+        }
+    });
+    Ok(server)
+}
+
+/// Reads the request of a connection, waits for the response of the event loop and writes it back.
+fn handle_connection<F: Fn(RemoteRequest) -> bool>(stream: TcpStream, server: &Server, forward: &F) {
+    if let Err(error) = stream.set_read_timeout(Some(IO_TIMEOUT)).and_then(|_| stream.set_write_timeout(Some(IO_TIMEOUT))) {
+        log::warn!("[remote::handle_connection()] Cannot set the timeouts: {error}");
+        return;
+    }
+    let mut reader = BufReader::new(&stream);
+    let (sender, receiver) = mpsc::channel();
+    let response = match read_request(&mut reader, sender).and_then(|request| authorize(request, server)) {
+        Ok(request) => {
+            log::debug!("Remote request: {} {}", request.method, request.path);
+            match forward(request) {
+                true => receiver.recv().unwrap_or_else(|_| Response::error(500, "No response")),
+                false => Response::error(503, "The viewer is closing"),
+            }
+        },
+        Err(response) => response,
+    };
+    if let Err(error) = write_response(&stream, &response) {
+        log::warn!("[remote::handle_connection()] Cannot answer the request: {error}");
+    }
+}
+
+/// Lets only requests pass, which carry the token and come from a local program. Browsers send
+/// an Origin header with the requests of web pages, and after a DNS rebinding they send the name
+/// of the foreign host.
+fn authorize(request: RemoteRequest, server: &Server) -> Result<RemoteRequest, Response> {
+    if request.header("origin").is_some() {
+        return Err(Response::error(403, "Requests from web pages are not allowed"));
+    }
+    let host = request.header("host").unwrap_or_default();
+    if host != format!("127.0.0.1:{}", server.port) && host != format!("localhost:{}", server.port) {
+        return Err(Response::error(403, &format!("Unknown host '{host}'")));
+    }
+    let token = request.header("authorization").and_then(|value| value.strip_prefix("Bearer "));
+    match token {
+        Some(token) if same(token.trim().as_bytes(), server.token.as_bytes()) => Ok(request),
+        _ => Err(Response::error(401, "The request needs the token of the server")),
+    }
+}
+
+/// Compares two secrets in a time, which does not depend on the position of the first difference.
+fn same(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |difference, (x, y)| difference | (x ^ y)) == 0
+}
+
+/// A token of 128 bits from the random source of the operating system as hex digits.
+fn random_token() -> std::io::Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|error| std::io::Error::other(error.to_string()))?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Reads the request line, the headers and the body of a HTTP request. A malformed request is
+/// answered at once by the returned response.
+fn read_request(reader: &mut impl BufRead, reply: Sender<Response>) -> Result<RemoteRequest, Response> {
+    let bad = |message: String| Response::error(400, &message);
+    let mut head = 0;
+    let line = read_line(reader, &mut head).map_err(bad)?;
+    let mut words = line.split_whitespace();
+    let (method, target) = match (words.next(), words.next()) {
+        (Some(method), Some(target)) => (method.to_string(), target.to_string()),
+        _ => return Err(bad(format!("Bad request line '{}'", line.trim()))),
+    };
+
+    let mut headers = Vec::new();
+    loop {
+        let header = read_line(reader, &mut head).map_err(bad)?;
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        match header.split_once(':') {
+            Some((name, value)) => headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string())),
+            None => return Err(bad(format!("Bad header '{header}'"))),
+        }
+    }
+    let length = match headers.iter().find(|(name, _)| name == "content-length") {
+        Some((_, value)) => value.parse().map_err(|_| bad(format!("Bad content length '{value}'")))?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(Response::error(413, &format!("The body must not be larger than {MAX_BODY} bytes")));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|error| bad(error.to_string()))?;
+    let body = String::from_utf8(body).map_err(|_| bad(String::from("The body is no UTF-8 text")))?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query = query.split('&')
+        .filter(|parameter| !parameter.is_empty())
+        .map(|parameter| {
+            let (key, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+    Ok(RemoteRequest { method, path: path.to_string(), query, headers, body, reply })
+}
+
+/// Reads a line of the head of a request. The head must not grow beyond MAX_HEAD.
+///
+/// # Arguments
+///
+/// * 'reader' - The connection.
+/// * 'head' - The bytes of the head, which were read so far.
+fn read_line(reader: &mut impl BufRead, head: &mut usize) -> Result<String, String> {
+    let mut line = String::new();
+    let read = reader.by_ref().take((MAX_HEAD - *head) as u64).read_line(&mut line).map_err(|error| error.to_string())?;
+    *head += read;
+    if line.ends_with('\n') {
+        Ok(line)
+    } else if *head >= MAX_HEAD {
+        Err(format!("The head of the request must not be larger than {MAX_HEAD} bytes"))
+    } else {
+        Err(String::from("The request ended too early"))
+    }
+}
+
+fn write_response(mut stream: &TcpStream, response: &Response) -> std::io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    write!(stream, "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                    Connection: close\r\n\r\n{}\n",
+           response.status, response.body.len() + 1, response.body)?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<RemoteRequest, Response> {
+        let (sender, _) = mpsc::channel();
+        read_request(&mut text.as_bytes(), sender)
+    }
+
+    #[test]
+    fn reads_request_line_headers_and_query() {
+        let request = parse("GET /pick?x=10&y=20 HTTP/1.1\r\nHost: 127.0.0.1:7878\r\nX-Thing:  a b \r\n\r\n").unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/pick");
+        assert_eq!(request.parameter("x"), Some("10"));
+        assert_eq!(request.parameter("y"), Some("20"));
+        assert_eq!(request.header("host"), Some("127.0.0.1:7878"));
+        assert_eq!(request.header("x-thing"), Some("a b"));
+        assert_eq!(request.body, "");
+    }
+
+    #[test]
+    fn reads_body_by_content_length() {
+        let request = parse("POST /command HTTP/1.1\r\nContent-Length: 10\r\n\r\nscale 2\nxyz").unwrap();
+        assert_eq!(request.body, "scale 2\nxy");
+    }
+
+    #[test]
+    fn rejects_body_beyond_limit() {
+        let response = parse(&format!("POST /command HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1)).unwrap_err();
+        assert_eq!(response.status, 413);
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        for text in ["", "GET\r\n\r\n", "GET / HTTP/1.1\r\nNo colon\r\n\r\n",
+                     "POST / HTTP/1.1\r\nContent-Length: ten\r\n\r\n",
+                     "POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nab",
+                     "GET / HTTP/1.1\r\nHost: localhost"] {
+            assert_eq!(parse(text).unwrap_err().status, 400, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_huge_head() {
+        let text = format!("GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n", "a".repeat(MAX_HEAD));
+        assert_eq!(parse(&text).unwrap_err().status, 400);
+    }
+
+    #[test]
+    fn authorizes_only_local_requests_with_token() {
+        let server = Server { port: 7878, token: String::from("secret") };
+        let request = |headers: &str| parse(&format!("GET /state HTTP/1.1\r\n{headers}\r\n")).unwrap();
+        let status = |headers: &str| authorize(request(headers), &server).map_or_else(|response| response.status, |_| 200);
+        assert_eq!(status("Host: 127.0.0.1:7878\r\nAuthorization: Bearer secret\r\n"), 200);
+        assert_eq!(status("Host: localhost:7878\r\nAuthorization: Bearer secret\r\n"), 200);
+        assert_eq!(status("Host: 127.0.0.1:7878\r\n"), 401);
+        assert_eq!(status("Host: 127.0.0.1:7878\r\nAuthorization: Bearer wrong\r\n"), 401);
+        assert_eq!(status("Host: evil.example:7878\r\nAuthorization: Bearer secret\r\n"), 403);
+        assert_eq!(status("Host: 127.0.0.1:7878\r\nOrigin: http://evil.example\r\nAuthorization: Bearer secret\r\n"), 403);
+    }
+
+    #[test]
+    fn encodes_responses_as_json() {
+        assert_eq!(Response::error(400, "a \"b\" \\ c\n").body, "{\"ok\":false,\"error\":\"a \\\"b\\\" \\\\ c\\n\"}");
+        assert_eq!(Response::ok(&Output { output: vec![String::from("grüße")] }).body,
+                   "{\"ok\":true,\"output\":[\"grüße\"]}");
+        assert_eq!(Response::ok(&Picked::of(None)).body, "{\"ok\":true,\"pick\":null}");
+        let object = ObjectState { position: [1.0, f32::INFINITY, -0.5], rotation: [0.0; 3], scale: 1.0 };
+        assert_eq!(serde_json::to_string(&object).unwrap(),
+                   "{\"position\":[1.0,null,-0.5],\"rotation\":[0.0,0.0,0.0],\"scale\":1.0}");
+    }
+
+    #[test]
+    fn round_trip_over_loopback() {
+        let (sender, receiver) = mpsc::channel();
+        let server = start(0, move |request| sender.send(request).is_ok()).unwrap();
+        std::thread::spawn(move || {
+            for request in receiver {
+                let request: RemoteRequest = request;
+                request.reply(Response::ok(&Output { output: vec![request.body.clone()] }));
+            }
+        });
+
+        let mut stream = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+        write!(stream, "POST /command HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nAuthorization: Bearer {}\r\n\
+                        Content-Length: 7\r\n\r\nscale 2", server.port, server.token).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.ends_with("{\"ok\":true,\"output\":[\"scale 2\"]}\n"), "{response}");
+
+        let mut stream = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+        write!(stream, "GET /state HTTP/1.1\r\nHost: 127.0.0.1:{}\r\n\r\n", server.port).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"), "{response}");
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::{Path, PathBuf};
+
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::{Lens, Projection};
+use crate::changes::Change;
+use crate::model::Model;
+use crate::navigation::Navigation;
+use crate::units::Unit;
+
+/// The number of bookmarks, which may be stored. They are recalled with the keys 1 to 9.
+pub const BOOKMARK_SLOTS: usize = 9;
+
+/// The complete state of the camera and the object transform, which may be stored and recalled.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub view_position: [f32; 3],
+    pub view_direction: [f32; 3],
+    pub up: [f32; 3],
+    pub rot: [f32; 3],
+    pub scaling_factor: f32,
+    pub object_position: [f32; 3],
+}
+impl Bookmark {
+    /// The current camera and object transform of the model.
+    pub fn of(model: &Model) -> Self {
+        Self {
+            view_position: model.view_position,
+            view_direction: model.view_direction,
+            up: model.up,
+            rot: model.rot,
+            scaling_factor: model.scaling_factor,
+            object_position: model.object_position.into(),
+        }
+    }
+
+    /// Puts the camera and the object back into the stored state.
+    pub fn apply(&self, model: &mut Model) {
+        model.view_position = self.view_position;
+        model.view_direction = self.view_direction;
+        model.up = self.up;
+        model.rot = self.rot;
+        model.scaling_factor = self.scaling_factor;
+        model.object_position = Vector3::from(self.object_position);
+        model.notify(Change::Camera);
+        model.notify(Change::Transform);
+    }
+}
+
+/// A bookmark in the session file, which knows its slot.
+#[derive(Serialize, Deserialize)]
+struct StoredBookmark {
+    slot: usize,
+    #[serde(flatten)]
+    bookmark: Bookmark,
+}
+
+/// The settings, which decide, what is shown and how.
+#[derive(Serialize, Deserialize)]
+struct DisplaySettings {
+    show_grid: bool,
+    show_axes: bool,
+    show_gizmo: bool,
+    show_bounds: bool,
+    show_oriented_bounds: bool,
+    #[serde(default)]
+    show_ground: bool,
+    navigation: Navigation,
+    projection: Projection,
+    lens: Lens,
+}
+
+/// The content of a session file: The loaded object, the units, the display settings, the current
+/// camera and the bookmarks. The format and the style of the HUD, the lights and the shadows are
+/// not part of it. They come from the command line and the lights from lighting.toml.
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    /// The obj file, which was shown in the session.
+    pub file: PathBuf,
+    model_unit: Unit,
+    unit: Unit,
+    display: DisplaySettings,
+    camera: Bookmark,
+    #[serde(default)]
+    bookmarks: Vec<StoredBookmark>,
+}
+impl Session {
+    /// Captures the current session of the model.
+    pub fn of(model: &Model) -> Self {
+        let file = std::fs::canonicalize(&model.file_name).unwrap_or_else(|_| PathBuf::from(&model.file_name));
+        let bookmarks = model.bookmarks.iter().enumerate()
+            .filter_map(|(i, bookmark)| bookmark.map(|bookmark| StoredBookmark { slot: i + 1, bookmark }))
+            .collect();
+        Self {
+            file,
+            model_unit: model.model_unit,
+            unit: model.unit,
+            display: DisplaySettings {
+                show_grid: model.show_grid,
+                show_axes: model.show_axes,
+                show_gizmo: model.show_gizmo,
+                show_bounds: model.show_bounds,
+                show_oriented_bounds: model.show_oriented_bounds,
+                show_ground: model.show_ground,
+                navigation: model.navigation,
+                projection: model.projection,
+                lens: model.lens,
+            },
+            camera: Bookmark::of(model),
+            bookmarks,
+        }
+    }
+
+    /// Reads a session file. A relative path of the obj file is taken relative to the session
+    /// file.
+    pub fn read(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut session: Session = toml::from_str(&std::fs::read_to_string(path)?)?;
+        if session.file.is_relative() {
+            if let Some(dir) = path.parent() {
+                session.file = dir.join(&session.file);
+            }
+        }
+        Ok(session)
+    }
+
+    /// Writes the session file.
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Puts the model into the state of the session. The object of the session must already be
+    /// loaded into the model.
+    pub fn apply(&self, model: &mut Model) {
+        model.model_unit = self.model_unit;
+        model.unit = self.unit;
+        model.show_grid = self.display.show_grid;
+        model.show_axes = self.display.show_axes;
+        model.show_gizmo = self.display.show_gizmo;
+        model.show_bounds = self.display.show_bounds;
+        model.show_oriented_bounds = self.display.show_oriented_bounds;
+        model.show_ground = self.display.show_ground;
+        model.navigation = self.display.navigation;
+        model.projection = self.display.projection;
+        model.lens = self.display.lens;
+        model.notify(Change::Display);
+        self.camera.apply(model);
+        model.bookmarks = [None; BOOKMARK_SLOTS];
+        for stored in &self.bookmarks {
+            match model.bookmarks.get_mut(stored.slot.wrapping_sub(1)) {
+                Some(slot) => *slot = Some(stored.bookmark),
+                None => log::warn!("[Session::apply()] Bookmark {} is ignored, only 1 to {BOOKMARK_SLOTS} exist",
+                                   stored.slot),
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use glium::{Display, IndexBuffer, Program, Surface, VertexBuffer};
+use glium::framebuffer::SimpleFrameBuffer;
+use glium::texture::{DepthFormat, DepthTexture2d, MipmapsOption};
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, Sampler, SamplerWrapFunction};
+use nalgebra::{Matrix4, Point3, Vector3};
+
+use crate::lighting::LightUniforms;
+use crate::model::{Model, Vertex};
+use crate::performance::DrawStats;
+use crate::view::{view_matrix, world_matrix};
+
+/// The width and the height of the shadow map in pixels.
+pub const SHADOW_MAP_SIZE: u32 = 2048;
+
+// The renderers, which draw without a graphics card. They are too slow for the extra pass of the
+// shadows.
+const SOFTWARE_RENDERERS: [&str; 5] = ["llvmpipe", "softpipe", "swrast", "software rasterizer", "swiftshader"];
+
+/// The shadows, which the key light casts. The depth of the object, as seen from the light, is
+/// rendered into the shadow map first. The fragments of the object and of the ground plane,
+/// which lie deeper than the map, are in the shadow.
+pub struct ShadowView {
+    map: DepthTexture2d,
+    program: Program,
+}
+impl ShadowView {
+    pub fn new(display: &Display) -> Self {
+        let map = DepthTexture2d::empty_with_format(display, DepthFormat::F32, MipmapsOption::NoMipmap,
+                                                    SHADOW_MAP_SIZE, SHADOW_MAP_SIZE).unwrap();
+        let program = Program::from_source(display, include_str!("shadow.vertex.glsl"),
+                                           include_str!("shadow.fragment.glsl"), None).unwrap();
+        Self { map, program }
+    }
+
+    /// Renders the object into the shadow map. Nothing is rendered, if the shadows are off or
+    /// there is no light.
+    ///
+    /// # Arguments
+    ///
+    /// * 'display' - The display, which owns the shadow map.
+    /// * 'model' - The model with the object.
+    /// * 'positions', 'indices' - The buffers of the object.
+    /// * 'lights' - The lights in world coordinates. The first one casts the shadows.
+    /// * 'stats' - Counts the draw call.
+    ///
+    /// Returns the transformation of world coordinates into the coordinates of the shadow map.
+    pub fn draw(&self, display: &Display, model: &Model, positions: &VertexBuffer<Vertex>, indices: &IndexBuffer<u16>,
+                lights: &LightUniforms, stats: &mut DrawStats) -> Option<[[f32; 4]; 4]> {
+        if !model.show_shadows {
+            return None;
+        }
+        let world = world_matrix(model);
+        let center = world.transform_point(&Point3::from((model.bounds.min + model.bounds.max) / 2.0)).coords;
+        let direction = lights.key_direction(&center)?;
+        let light_matrix = light_matrix(model, &world, &center, &direction);
+
+        let mut target = SimpleFrameBuffer::depth_only(display, &self.map).unwrap();
+        target.clear_depth(1.0);
+        let model_matrix: [[f32; 4]; 4] = world.into();
+        let params = glium::DrawParameters {
+            depth: glium::Depth {
+                test: glium::draw_parameters::DepthTest::IfLess,
+                write: true,
+                .. Default::default()
+            },
+            .. Default::default()
+        };
+        target.draw(positions, indices, &self.program,
+                    &uniform! { model: model_matrix, u_light_matrix: light_matrix }, &params).unwrap();
+        stats.count(indices.len() / 3);
+        Some(light_matrix)
+    }
+
+    /// The shadow map, as the shader of the object samples it.
+    pub fn sampled(&self) -> Sampler<'_, DepthTexture2d> {
+        self.map.sampled()
+            .magnify_filter(MagnifySamplerFilter::Nearest)
+            .minify_filter(MinifySamplerFilter::Nearest)
+            .wrap_function(SamplerWrapFunction::Clamp)
+    }
+}
+
+/// Decides, whether the key light casts shadows on the display. They are turned off, when OpenGL
+/// runs without a graphics card, as the extra pass is too slow there.
+///
+/// # Arguments
+///
+/// * 'display' - The display, which renders the object.
+/// * 'shadows' - True, if the shadows are wanted.
+pub fn shadows_on(display: &Display, shadows: bool) -> bool {
+    if shadows && is_software_renderer(display) {
+        log::info!("The shadows are off, because {} renders without a graphics card", display.get_opengl_renderer_string());
+        return false;
+    }
+    shadows
+}
+
+/// Decides by the name of the renderer, whether OpenGL runs without a graphics card.
+fn is_software_renderer(display: &Display) -> bool {
+    let renderer = display.get_opengl_renderer_string().to_lowercase();
+    SOFTWARE_RENDERERS.iter().any(|name| renderer.contains(name))
+}
+
+/// The orthographic projection along the direction of the key light. Its sides are fitted to the
+/// bounding box of the object, which casts the shadows. The depth range also covers the ground
+/// plane, which receives them.
+///
+/// # Arguments
+///
+/// * 'model' - The model with the bounding box of the object.
+/// * 'world' - The transformation of the object into world coordinates.
+/// * 'center' - The center of the object in world coordinates.
+/// * 'direction' - The direction towards the light.
+fn light_matrix(model: &Model, world: &Matrix4<f32>, center: &Vector3<f32>, direction: &Vector3<f32>) -> [[f32; 4]; 4] {
+    let up = if direction.y.abs() > 0.99 { [0.0, 0.0, 1.0] } else { [0.0, 1.0, 0.0] };
+    let view: Matrix4<f32> = view_matrix(&(*center).into(), &(-direction).into(), &up).into();
+
+    let casters = model.bounds.corners().map(|corner| view.transform_point(&world.transform_point(&Point3::from(corner))));
+    let (mut min, mut max) = (casters[0].coords, casters[0].coords);
+    for corner in &casters {
+        min = min.inf(&corner.coords);
+        max = max.sup(&corner.coords);
+    }
+    if model.show_ground {
+        let (ground_center, half_size) = model.ground_plane();
+        for (x, z) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+            let corner = ground_center + Vector3::new(x * half_size, 0.0, z * half_size);
+            let depth = view.transform_point(&Point3::from(corner)).z;
+            min.z = min.z.min(depth);
+            max.z = max.z.max(depth);
+        }
+    }
+    // A small margin keeps the outermost texels and depths inside the map.
+    let margin = 0.01 * (max - min).norm().max(f32::EPSILON);
+    let (min, max) = (min.add_scalar(-margin), max.add_scalar(margin));
+
+    let projection = Matrix4::new(2.0 / (max.x - min.x), 0.0, 0.0, -(max.x + min.x) / (max.x - min.x),
+                                  0.0, 2.0 / (max.y - min.y), 0.0, -(max.y + min.y) / (max.y - min.y),
+                                  0.0, 0.0, 2.0 / (max.z - min.z), -(max.z + min.z) / (max.z - min.z),
+                                  0.0, 0.0, 0.0, 1.0);
+    (projection * view).into()
+}
@@ -0,0 +1,12 @@
+#version 150
+
+in vec3 position;
+
+uniform mat4 model;
+uniform mat4 u_light_matrix;
+
+// The model matrix already contains the offset of the object, so the depth is rendered in the
+// same place, where the object is drawn.
+void main() {
+    gl_Position = u_light_matrix * model * vec4(position, 1.0);
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use obj::Obj;
+use obj::raw::RawObj;
+
+/// The numbers of the loaded obj file, which are presented in the HUD.
+#[derive(Clone, Debug, Default)]
+pub struct MeshStatistics {
+    /// The vertices, which are sent to the graphics card. Each combination of a position and a
+    /// normal is one vertex.
+    pub vertices: usize,
+    /// The positions in the obj file.
+    pub positions: usize,
+    pub triangles: usize,
+    /// The objects ("o") of the obj file.
+    pub objects: usize,
+    /// The groups ("g") of the obj file, which contain triangles.
+    pub groups: usize,
+    /// The materials ("usemtl"), which are used by the obj file.
+    pub materials: usize,
+    /// The time to read and parse the obj file and to build the picking structure.
+    pub load_time: Duration,
+}
+impl MeshStatistics {
+    /// Counts the content of a parsed obj file. The vertices and the load time are only known,
+    /// when the object has been built, see count_object().
+    ///
+    /// # Arguments
+    ///
+    /// * 'input' - The content of the obj file.
+    /// * 'raw' - The parsed obj file.
+    /// * 'groups' - The number of groups with triangles.
+    pub fn new(input: &[u8], raw: &RawObj, groups: usize) -> Self {
+        // The raw obj file only keeps the name of the last object, so the objects are counted in
+        // the file itself.
+        let objects = input.split(|byte| *byte == b'\n')
+            .filter(|line| line.trim_ascii_start().starts_with(b"o "))
+            .count();
+        Self {
+            vertices: 0,
+            positions: raw.positions.len(),
+            triangles: raw.polygons.len(),
+            objects,
+            groups,
+            materials: raw.meshes.len(),
+            load_time: Duration::ZERO,
+        }
+    }
+
+    /// Counts the vertices and triangles of the object, which is rendered.
+    ///
+    /// # Arguments
+    ///
+    /// * 'object' - The object built from the obj file.
+    /// * 'load_time' - The time since the loading started.
+    pub fn count_object(&mut self, object: &Obj, load_time: Duration) {
+        self.vertices = object.vertices.len();
+        self.triangles = object.indices.len() / 3;
+        self.load_time = load_time;
+    }
+}
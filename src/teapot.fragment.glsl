@@ -1,22 +1,70 @@
 #version 150
 
+const int MAX_LIGHTS = 4;
+
 in vec3 v_normal;
 in vec3 v_position;
+in vec4 v_shadow_position;
 
 out vec4 color;
 
-uniform vec3 u_light;
+uniform vec3 u_eye;
+uniform vec3 u_ambient;
+uniform int u_light_count;
+// A position with w = 0 is the direction towards a directional light.
+uniform vec4 u_light_position[MAX_LIGHTS];
+// The color of each light, multiplied with its intensity.
+uniform vec3 u_light_color[MAX_LIGHTS];
+// The diffuse color of the surface.
+uniform vec3 u_color;
+// 0 without shadows, 1 for hard shadows and 2 for shadows with filtered edges.
+uniform int u_shadow_mode;
+// The depth of the object, as seen from the key light.
+uniform sampler2D u_shadow_map;
 
-const vec3 ambient_color = vec3(0.2, 0.0, 0.0);
-const vec3 diffuse_color = vec3(0.6, 0.0, 0.0);
 const vec3 specular_color = vec3(1.0, 1.0, 1.0);
 
+// The part of the key light, which reaches the fragment: 0 in the shadow and 1 in the light.
+float key_light(vec3 normal, vec3 light_dir) {
+    if (u_shadow_mode == 0) {
+        return 1.0;
+    }
+    vec3 coord = v_shadow_position.xyz / v_shadow_position.w * 0.5 + 0.5;
+    if (any(lessThan(coord.xy, vec2(0.0))) || any(greaterThan(coord.xy, vec2(1.0)))) {
+        return 1.0;
+    }
+    // Surfaces, which the light grazes, need a larger bias against shadow acne.
+    float depth = coord.z - max(0.004 * (1.0 - dot(normal, light_dir)), 0.001);
+    if (u_shadow_mode == 1) {
+        return depth > texture(u_shadow_map, coord.xy).r ? 0.0 : 1.0;
+    }
+    vec2 texel = 1.0 / vec2(textureSize(u_shadow_map, 0));
+    float light = 0.0;
+    for (int x = -1; x <= 1; x++) {
+        for (int y = -1; y <= 1; y++) {
+            light += depth > texture(u_shadow_map, coord.xy + vec2(x, y) * texel).r ? 0.0 : 1.0;
+        }
+    }
+    return light / 9.0;
+}
+
 void main() {
-    float diffuse = max(dot(normalize(v_normal), normalize(u_light)), 0.0);
+    vec3 normal = normalize(v_normal);
+    vec3 camera_dir = normalize(u_eye - v_position);
+    vec3 result = u_ambient * u_color;
+
+    for (int i = 0; i < u_light_count; i++) {
+        vec4 light = u_light_position[i];
+        vec3 light_dir = normalize(light.w == 0.0 ? light.xyz : light.xyz - v_position);
+        float diffuse = max(dot(normal, light_dir), 0.0);
+
+        vec3 half_direction = normalize(light_dir + camera_dir);
+        float specular = diffuse > 0.0 ? pow(max(dot(half_direction, normal), 0.0), 16.0) : 0.0;
 
-    vec3 camera_dir = normalize(-v_position);
-    vec3 half_direction = normalize(normalize(u_light) + camera_dir);
-    float specular = pow(max(dot(half_direction, normalize(v_normal)), 0.0), 16.0);
+        // Only the key light casts shadows.
+        float visible = i == 0 && diffuse > 0.0 ? key_light(normal, light_dir) : 1.0;
+        result += visible * u_light_color[i] * (diffuse * u_color + specular * specular_color);
+    }
 
-    color = vec4(ambient_color + diffuse * diffuse_color + specular * specular_color, 1.0);
+    color = vec4(result, 1.0);
 }
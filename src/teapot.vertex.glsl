@@ -5,18 +5,22 @@ in vec3 normal;
 
 out vec3 v_normal;
 out vec3 v_position;
+out vec4 v_shadow_position;
 
 uniform mat4 perspective;
 uniform mat4 view;
 uniform mat4 model;
 uniform vec3 offset;
+// Transforms world coordinates into the coordinates of the shadow map.
+uniform mat4 u_shadow_matrix;
 
-// We need to rotate first and move the object afterwards.
+// We need to rotate first and move the object afterwards. The offset moves the object in world
+// coordinates, so it stays in line with the reference grid and the world axes. The lighting
+// happens in world coordinates.
 void main() {
-    mat4 modelview = view * model;
-    v_normal = transpose(inverse(mat3(modelview))) * normal;
-    vec3 op = offset + position;
-    vec4 rot_pos = perspective * modelview * vec4(position, 1.0); // It seems that at this point something is going wrong.
-    gl_Position = rot_pos + vec4(offset, 1.0);
-    v_position = gl_Position.xyz / gl_Position.w;
+    v_normal = transpose(inverse(mat3(model))) * normal;
+    vec4 world_pos = model * vec4(position, 1.0) + vec4(offset, 0.0);
+    v_position = world_pos.xyz;
+    v_shadow_position = u_shadow_matrix * world_pos;
+    gl_Position = perspective * view * world_pos;
 }
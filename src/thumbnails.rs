@@ -0,0 +1,215 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::export::{hidden_display, write_png};
+use crate::lighting::Lighting;
+use crate::model::Model;
+use crate::shadow;
+use crate::view::{RenderOptions, View};
+
+/// The settings of the thumbnail generation.
+#[derive(Clone, Debug)]
+pub struct ThumbnailSettings {
+    pub width: u32,
+    pub height: u32,
+    /// The angle between the line of sight and the ground in degrees.
+    pub elevation: f32,
+    /// The directory, which receives the thumbnails and the index.
+    pub out: PathBuf,
+}
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        Self { width: 256, height: 256, elevation: 20.0, out: PathBuf::from("thumbnails") }
+    }
+}
+
+/// One entry of the index: The model and its thumbnail or the reason, why there is none.
+struct Entry {
+    model: PathBuf,
+    result: Result<PathBuf, String>,
+}
+
+/// Creates a thumbnail for each obj file in the directory tree. The thumbnails mirror the directory
+/// tree in the output directory, which also receives an index as HTML and JSON file. Files, which
+/// cannot be loaded or rendered, are reported and skipped.
+///
+/// Loading the models and writing the images runs in parallel on all cores. Rendering itself is
+/// done one by one, as there is only one OpenGL context.
+///
+/// # Arguments
+///
+/// * 'dir' - The root of the directory tree with the obj files.
+/// * 'settings' - The settings of the thumbnails.
+/// * 'lighting' - The lights of the objects.
+/// * 'shadows' - True, if the key light casts shadows.
+/// * 'transparent' - Renders the objects on a transparent background.
+pub fn render_thumbnails(dir: &Path, settings: &ThumbnailSettings, lighting: &Lighting, shadows: bool,
+                         transparent: bool)
+    -> Result<(), Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    let mut unreadable = Vec::new();
+    collect_obj_files(dir, dir, &mut files, &mut unreadable);
+    files.sort();
+    log::info!("{} obj files found in {}", files.len(), dir.display());
+
+    let (_event_loop, display) = hidden_display()?;
+    let shadows = shadow::shadows_on(&display, shadows);
+    let mut view: Option<View> = None;
+    let options = RenderOptions { hud: false, transparent };
+    let aspect_ratio = settings.width as f32 / settings.height as f32;
+    let chunk_size = 2 * rayon::current_num_threads();
+    let mut entries = unreadable;
+
+    for chunk in files.chunks(chunk_size) {
+        let models: Vec<Result<Model, String>> = chunk.par_iter()
+            .map(|file| Model::new(&file.to_string_lossy()).map_err(|error| error.to_string()))
+            .collect();
+
+        let mut images = Vec::new();
+        for (file, model) in chunk.iter().zip(models) {
+            let relative = file.strip_prefix(dir).unwrap_or(file).to_path_buf();
+            let image = model.and_then(|mut model| {
+                model.show_gizmo = false;
+                model.lighting = lighting.clone();
+                model.show_shadows = shadows;
+                model.frame_object(settings.elevation.to_radians(), aspect_ratio);
+                let view = match &mut view {
+                    Some(view) => {
+                        view.load_object(&display, &model);
+                        view
+                    }
+                    None => view.insert(View::new(&display, &model)),
+                };
+                view.render_image_sized(&display, &model, settings.width, settings.height, 1.0, &options)
+                    .map_err(|error| error.to_string())
+            });
+            images.push((relative, image));
+        }
+
+        let written: Vec<Entry> = images.into_par_iter()
+            .map(|(relative, image)| {
+                let thumbnail = relative.with_extension("png");
+                let result = image.and_then(|(width, height, pixels)| {
+                    let path = settings.out.join(&thumbnail);
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+                    }
+                    write_png(&path, width, height, &pixels).map_err(|error| error.to_string())?;
+                    Ok(thumbnail)
+                });
+                if let Err(error) = &result {
+                    log::error!("Skipping {}: {error}", relative.display());
+                }
+                Entry { model: relative, result }
+            })
+            .collect();
+        entries.extend(written);
+    }
+
+    std::fs::create_dir_all(&settings.out)?;
+    write_json_index(&settings.out.join("index.json"), &entries)?;
+    write_html_index(&settings.out.join("index.html"), &entries)?;
+
+    let failed = entries.iter().filter(|entry| entry.result.is_err()).count();
+    log::info!("{} thumbnails written, {} files failed", entries.len() - failed, failed);
+    if failed > 0 {
+        eprintln!("{failed} of {} files failed:", entries.len());
+        for entry in &entries {
+            if let Err(error) = &entry.result {
+                eprintln!("  {}: {error}", entry.model.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Collects all obj files in the directory tree. Links to directories are not followed, so a link
+/// loop cannot recurse forever. Directories and entries, which cannot be read, are reported and
+/// skipped.
+///
+/// # Arguments
+///
+/// * 'dir' - The directory, which is searched.
+/// * 'root' - The root of the directory tree, which the index refers to.
+/// * 'files' - Receives the obj files.
+/// * 'unreadable' - Receives the entries of the index for the paths, which cannot be read.
+fn collect_obj_files(dir: &Path, root: &Path, files: &mut Vec<PathBuf>, unreadable: &mut Vec<Entry>) {
+    let failed = |path: &Path, error: std::io::Error| {
+        let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+        log::error!("Skipping {}: {error}", relative.display());
+        Entry { model: relative, result: Err(error.to_string()) }
+    };
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => return unreadable.push(failed(dir, error)),
+    };
+    for entry in entries {
+        let (path, file_type) = match entry.and_then(|entry| Ok((entry.path(), entry.file_type()?))) {
+            Ok(entry) => entry,
+            Err(error) => {
+                unreadable.push(failed(dir, error));
+                continue;
+            }
+        };
+        if file_type.is_dir() {
+            collect_obj_files(&path, root, files, unreadable);
+        } else if file_type.is_symlink() && path.is_dir() {
+            log::warn!("Skipping the link to a directory {}", path.display());
+        } else if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("obj")) {
+            files.push(path);
+        }
+    }
+}
+
+/// One object of the JSON index: The model and its thumbnail or the reason, why there is none.
+#[derive(Serialize)]
+struct IndexEntry {
+    model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Writes the index as JSON array with one object per model.
+fn write_json_index(path: &Path, entries: &[Entry]) -> std::io::Result<()> {
+    let index: Vec<IndexEntry> = entries.iter()
+        .map(|entry| IndexEntry {
+            model: entry.model.to_string_lossy().into_owned(),
+            thumbnail: entry.result.as_ref().ok().map(|thumbnail| thumbnail.to_string_lossy().into_owned()),
+            error: entry.result.as_ref().err().cloned(),
+        })
+        .collect();
+    let mut out = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut out, &index)?;
+    writeln!(out)?;
+    out.flush()
+}
+
+/// Writes the index as HTML page, which shows the thumbnails in a grid.
+fn write_html_index(path: &Path, entries: &[Entry]) -> std::io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Thumbnails</title>")?;
+    writeln!(out, "<style>figure {{ display: inline-block; margin: 8px; }} .error {{ color: red; }}</style>")?;
+    writeln!(out, "</head>\n<body>")?;
+    for entry in entries {
+        let model = html_escape(&entry.model.to_string_lossy());
+        match &entry.result {
+            Ok(thumbnail) => writeln!(out, "<figure><img src=\"{}\"><figcaption>{model}</figcaption></figure>",
+                                      html_escape(&thumbnail.to_string_lossy()))?,
+            Err(error) => writeln!(out, "<figure class=\"error\"><figcaption>{model}: {}</figcaption></figure>",
+                                   html_escape(error))?,
+        }
+    }
+    writeln!(out, "</body>\n</html>")?;
+    out.flush()
+}
+
+/// Escapes the special characters of HTML.
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
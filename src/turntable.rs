@@ -0,0 +1,123 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use image::{Delay, Frame, RgbaImage};
+use image::codecs::gif::{GifEncoder, Repeat};
+use nalgebra::{UnitQuaternion, Vector3};
+
+use crate::export::{hidden_display, write_png};
+use crate::model::Model;
+use crate::shadow;
+use crate::view::{RenderOptions, View};
+
+/// The settings of a turntable animation.
+#[derive(Clone, Debug)]
+pub struct TurntableSettings {
+    pub frames: u32,
+    pub width: u32,
+    pub height: u32,
+    /// The angle between the line of sight and the ground in degrees.
+    pub elevation: f32,
+    /// A file ending with .gif receives an animated GIF, anything else is taken as directory for
+    /// the numbered PNG files.
+    pub out: PathBuf,
+    /// The delay between two frames of the GIF in milliseconds.
+    pub delay: u32,
+}
+impl Default for TurntableSettings {
+    fn default() -> Self {
+        Self { frames: 36, width: 512, height: 512, elevation: 20.0, out: PathBuf::from("turntable"), delay: 80 }
+    }
+}
+
+/// Renders the object without a window, while it makes a full turn around its vertical axis. The
+/// frames are written as numbered PNG files or as one animated GIF.
+///
+/// # Arguments
+///
+/// * 'model' - The model with the object to render.
+/// * 'settings' - The settings of the animation.
+/// * 'transparent' - Renders the object on a transparent background.
+pub fn render_turntable(model: &mut Model, settings: &TurntableSettings, transparent: bool)
+    -> Result<(), Box<dyn std::error::Error>> {
+    let (_event_loop, display) = hidden_display()?;
+    model.show_shadows = shadow::shadows_on(&display, model.show_shadows);
+    let mut view = View::new(&display, model);
+    let options = RenderOptions { hud: false, transparent };
+
+    let frames = settings.frames.max(1);
+    let start = model.rot;
+    model.show_gizmo = false;
+    model.frame_object(settings.elevation.to_radians(), settings.width as f32 / settings.height as f32);
+
+    let gif = settings.out.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+    if !gif {
+        std::fs::create_dir_all(&settings.out)?;
+    }
+
+    let mut images = Vec::new();
+    for i in 0..frames {
+        model.rot = spin(start, std::f32::consts::TAU * i as f32 / frames as f32);
+        let (width, height, pixels) = view.render_image_sized(&display, model, settings.width, settings.height,
+                                                              1.0, &options)?;
+        if gif {
+            images.push(RgbaImage::from_raw(width, height, pixels).ok_or("Frame has the wrong size")?);
+        } else {
+            let path = settings.out.join(format!("frame-{:04}.png", i + 1));
+            write_png(&path, width, height, &pixels)?;
+        }
+        log::info!("Frame {}/{} rendered", i + 1, frames);
+    }
+    model.rot = start;
+
+    if gif {
+        write_gif(&settings.out, images, settings.delay)?;
+    }
+    Ok(())
+}
+
+/// The rotation of the object, after it is turned around the vertical axis of the world. Turning
+/// one of the angles would wobble, as soon as the object is tilted.
+///
+/// # Arguments
+///
+/// * 'rot' - The rotation of the object as angles around x, y and z in RAD.
+/// * 'angle' - The angle of the turn in RAD.
+fn spin(rot: [f32; 3], angle: f32) -> [f32; 3] {
+    let turn = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), angle);
+    let (roll, pitch, yaw) = (turn * UnitQuaternion::from_euler_angles(rot[0], rot[1], rot[2])).euler_angles();
+    [roll, pitch, yaw]
+}
+
+/// Writes the images as animated GIF, which repeats forever.
+fn write_gif(path: &Path, images: Vec<RgbaImage>, delay: u32) -> image::ImageResult<()> {
+    let mut encoder = GifEncoder::new(File::create(path)?);
+    encoder.set_repeat(Repeat::Infinite)?;
+    encoder.encode_frames(images.into_iter()
+        .map(|image| Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(delay, 1))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotation(rot: [f32; 3]) -> UnitQuaternion<f32> {
+        UnitQuaternion::from_euler_angles(rot[0], rot[1], rot[2])
+    }
+
+    #[test]
+    fn spins_tilted_objects_around_the_vertical_axis() {
+        let tilted = [0.4, 0.3, -0.7];
+        let up = Vector3::y();
+        for i in 0..12 {
+            let angle = std::f32::consts::TAU * i as f32 / 12.0;
+            let turned = rotation(spin(tilted, angle));
+            // The vertical axis of the object keeps its tilt, so nothing wobbles.
+            let (before, after) = (rotation(tilted) * up, turned * up);
+            assert!((before.y - after.y).abs() < 1e-5, "{i}: {before} {after}");
+            let expected = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), angle) * rotation(tilted);
+            assert!(turned.angle_to(&expected) < 1e-4, "{i}");
+        }
+        assert!(rotation(spin(tilted, std::f32::consts::TAU)).angle_to(&rotation(tilted)) < 1e-4);
+    }
+}
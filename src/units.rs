@@ -0,0 +1,98 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// The length units, in which the dimensions of the object may be presented. OBJ files know
+/// nothing about units, so the unit of the file coordinates must be given separately.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Unit {
+    #[serde(rename = "mm")]
+    Millimeter,
+    #[serde(rename = "cm")]
+    Centimeter,
+    #[serde(rename = "m")]
+    Meter,
+    #[serde(rename = "in", alias = "inch")]
+    Inch,
+}
+impl Unit {
+    /// The length of one unit in meters.
+    pub fn meters(&self) -> f32 {
+        match self {
+            Unit::Millimeter => 0.001,
+            Unit::Centimeter => 0.01,
+            Unit::Meter => 1.0,
+            Unit::Inch => 0.0254,
+        }
+    }
+
+    /// The abbreviation of the unit, as it is used for the labels.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Millimeter => "mm",
+            Unit::Centimeter => "cm",
+            Unit::Meter => "m",
+            Unit::Inch => "in",
+        }
+    }
+
+    /// The next unit in the cycle mm -> cm -> m -> in -> mm.
+    pub fn next(&self) -> Self {
+        match self {
+            Unit::Millimeter => Unit::Centimeter,
+            Unit::Centimeter => Unit::Meter,
+            Unit::Meter => Unit::Inch,
+            Unit::Inch => Unit::Millimeter,
+        }
+    }
+
+    /// Converts a length given in this unit into the provided unit.
+    ///
+    /// # Arguments
+    ///
+    /// * 'value' - The length in this unit.
+    /// * 'unit' - The unit of the result.
+    pub fn convert(&self, value: f32, unit: Unit) -> f32 {
+        value * self.meters() / unit.meters()
+    }
+}
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mm" => Ok(Unit::Millimeter),
+            "cm" => Ok(Unit::Centimeter),
+            "m" => Ok(Unit::Meter),
+            "in" | "inch" => Ok(Unit::Inch),
+            _ => Err(format!("Unknown unit '{s}', expected one of mm, cm, m, in")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Units {
+        unit: Unit,
+    }
+
+    #[test]
+    fn stores_units_by_their_symbols() {
+        for unit in [Unit::Millimeter, Unit::Centimeter, Unit::Meter, Unit::Inch] {
+            let text = toml::to_string(&Units { unit }).unwrap();
+            assert_eq!(text.trim(), format!("unit = \"{}\"", unit.symbol()));
+            assert_eq!(toml::from_str::<Units>(&text).unwrap(), Units { unit });
+        }
+        assert_eq!(toml::from_str::<Units>("unit = \"inch\"").unwrap(), Units { unit: Unit::Inch });
+        assert!(toml::from_str::<Units>("unit = \"furlong\"").is_err());
+    }
+}
@@ -1,20 +1,139 @@
 use crate::model::{Model, Vertex};
+use crate::bindings::Bindings;
 use crate::hud::HudView;
+use crate::reference::ReferenceView;
+use crate::dimensions::DimensionView;
+use crate::highlight::HighlightView;
+use crate::lighting::{LightUniforms, WithLights};
+use crate::shadow::ShadowView;
+use crate::annotations::AnnotationView;
+use crate::camera::Projection;
+use crate::changes::{Change, Subscription};
+use crate::performance::{DrawStats, Performance};
 
-use glium::{Display, IndexBuffer, Program, Surface, VertexBuffer, Frame};
-use nalgebra::Matrix4;
+use std::time::Instant;
+
+use glium::{Display, IndexBuffer, Program, Surface, Texture2d, VertexBuffer};
+use glium::framebuffer::{DepthRenderBuffer, SimpleFrameBuffer};
+use glium::texture::{DepthFormat, MipmapsOption, RawImage2d, UncompressedFloatFormat};
+use nalgebra::{Matrix4, Vector3, Vector4};
+
+/// The place of the object in the world, before it is moved around.
+pub const OBJECT_HOME: [f32; 3] = [0.0, 0.0, 2.0];
+
+/// The vertical field of view of the perspective.
+pub const FIELD_OF_VIEW: f32 = std::f32::consts::PI / 3.0;
+
+// The largest tile, which is rendered at once into an offscreen image.
+const MAX_TILE_SIZE: u32 = 4096;
+
+/// The largest offscreen image in pixels. Its RGBA pixels take 1 GB.
+pub const MAX_IMAGE_PIXELS: u64 = 1 << 28;
+
+const OBJECT_COLOR: [f32; 3] = [0.6, 0.0, 0.0];
+const GROUND_COLOR: [f32; 3] = [0.55, 0.55, 0.55];
+
+/// The part of the picture, which is rendered into the current target. Usually the target shows
+/// the complete picture. Pictures, which are larger than the graphics card allows, are rendered in
+/// tiles. The tile matrix moves the part of the picture, which belongs to the tile, into the
+/// target.
+#[derive(Copy, Clone, Debug)]
+pub struct Viewport {
+    /// The width of the complete picture in pixels.
+    pub width: u32,
+    /// The height of the complete picture in pixels.
+    pub height: u32,
+    /// The scale of the HUD and the annotations, compared to the window.
+    pub scale: f32,
+    tile: Matrix4<f32>,
+}
+impl Viewport {
+    /// A viewport, which shows the complete picture in the target.
+    ///
+    /// # Arguments
+    ///
+    /// * 'width', 'height' - The size of the picture in pixels.
+    /// * 'scale' - The scale of the HUD and the annotations, e.g. the scale factor of the window.
+    pub fn full(width: u32, height: u32, scale: f32) -> Self {
+        Self { width, height, scale, tile: Matrix4::identity() }
+    }
+
+    /// A viewport, which shows only a tile of the picture in the target.
+    ///
+    /// # Arguments
+    ///
+    /// * 'width', 'height' - The size of the complete picture in pixels.
+    /// * 'scale' - The scale of the HUD and the annotations.
+    /// * 'left', 'bottom', 'tile_width', 'tile_height' - The area of the tile within the picture.
+    pub fn tile(width: u32, height: u32, scale: f32,
+                left: u32, bottom: u32, tile_width: u32, tile_height: u32) -> Self {
+        let sx = width as f32 / tile_width as f32;
+        let sy = height as f32 / tile_height as f32;
+        let tx = sx - 1.0 - 2.0 * left as f32 / tile_width as f32;
+        let ty = sy - 1.0 - 2.0 * bottom as f32 / tile_height as f32;
+        let tile = Matrix4::new(sx, 0.0, 0.0, tx,
+                                0.0, sy, 0.0, ty,
+                                0.0, 0.0, 1.0, 0.0,
+                                0.0, 0.0, 0.0, 1.0);
+        Self { width, height, scale, tile }
+    }
+
+    /// The projection of the picture, restricted to the tile.
+    pub fn projection(&self, model: &Model) -> [[f32; 4]; 4] {
+        let projection: Matrix4<f32> = projection_matrix(model, self.width, self.height).into();
+        (self.tile * projection).into()
+    }
+
+    /// Restricts any other projection of the picture to the tile.
+    pub fn restrict(&self, projection: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
+        let projection: Matrix4<f32> = projection.into();
+        (self.tile * projection).into()
+    }
+
+    /// The projection of the HUD, which is laid out in pixels of the complete picture, starting at
+    /// the top left corner.
+    pub fn hud_transform(&self) -> [[f32; 4]; 4] {
+        self.restrict([
+            [2.0 / self.width as f32, 0.0, 0.0, 0.0],
+            [0.0, -2.0 / self.height as f32, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [-1.0, 1.0, 0.0, 1.0],
+        ])
+    }
+}
+
+/// The options of rendering a picture.
+#[derive(Copy, Clone, Debug)]
+pub struct RenderOptions {
+    pub hud: bool,
+    pub transparent: bool,
+}
 
 pub struct View {
     positions: VertexBuffer<Vertex>,
     indices: IndexBuffer<u16>,
     program: Program,
+    reference: ReferenceView,
+    dimensions: DimensionView,
+    highlight: HighlightView,
+    annotations: AnnotationView,
     hud: HudView,
+    shadow: ShadowView,
+    /// A square of edge length 2 in the xz plane, which is scaled to the ground plane.
+    ground: (VertexBuffer<Vertex>, IndexBuffer<u16>),
+    /// The view is redrawn after any change of the model.
+    subscription: Subscription,
+    /// The buffers of the object are replaced, when another object is loaded.
+    geometry: Subscription,
+    performance: Performance,
 }
 impl View {
     pub fn new(display: &Display, model: &Model) -> Self
     {
+        let start = Instant::now();
         let positions = model.object.vertex_buffer(display).unwrap();
         let indices = model.object.index_buffer(display).unwrap();
+        let upload_time = start.elapsed();
 
         let vertex_shader_src = include_str!("teapot.vertex.glsl");
         let fragment_shader_src = include_str!("teapot.fragment.glsl");
@@ -26,71 +145,327 @@ impl View {
         // let dejavu_font = FontRef::try_from_slice(dejavu).unwrap();
 
         // let glyph_brush = GlyphBrushBuilder::using_font(dejavu_font).build(display);
-        let hud = HudView::new(display);
+        let reference = ReferenceView::new(display, model);
+        let dimensions = DimensionView::new(display);
+        let highlight = HighlightView::new(display);
+        let annotations = AnnotationView::new(display);
+        let mut hud = HudView::new(display, &model.hud_style);
+        hud.set_upload_time(upload_time);
+        let shadow = ShadowView::new(display);
+        let ground = ground_buffers(display);
+
+        let subscription = Subscription::new(&Change::ALL);
+        // The object of the model is already uploaded.
+        let mut geometry = Subscription::new(&[Change::Geometry]);
+        geometry.take(model.changes());
+
+        Self { positions, indices, program, reference, dimensions, highlight, annotations, hud, shadow, ground,
+               subscription, geometry, performance: Performance::default() }
+    }
 
-        Self { positions, indices, program, hud }
+    /// Replaces the object, which is drawn, by the object of the model.
+    pub fn load_object(&mut self, display: &Display, model: &Model) {
+        let start = Instant::now();
+        self.positions = model.object.vertex_buffer(display).unwrap();
+        self.indices = model.object.index_buffer(display).unwrap();
+        self.hud.set_upload_time(start.elapsed());
     }
 
-    pub fn draw(&mut self, display: &Display, model: &Model) {
-        if model.has_changed() {
+    /// Draws the model into the window, if it has changed since the last frame.
+    ///
+    /// # Arguments
+    ///
+    /// * 'display' - The display of the window.
+    /// * 'model' - The model to draw.
+    /// * 'bindings' - The key bindings, which are listed in the help.
+    pub fn draw(&mut self, display: &Display, model: &Model, bindings: &Bindings) {
+        // When rendering continuously, the subscription must still see the changes.
+        let changed = self.subscription.take(model.changes());
+        if self.geometry.take(model.changes()) {
+            self.load_object(display, model);
+        }
+        if changed || model.render_continuously {
+            if model.show_help {
+                self.hud.set_help(bindings.help(model.navigation));
+            }
+            self.performance.begin_frame(display);
             let mut target = display.draw();
-            self.draw_object(&mut target, model);
-            self.hud.draw(&mut target, display, model);
+            let (width, height) = target.get_dimensions();
+            let options = RenderOptions { hud: true, transparent: false };
+            let viewport = Viewport::full(width, height, scale_factor(display));
+            let stats = self.render(display, &mut target, model, &viewport, &options);
+            // The CPU time ends before the buffers are swapped, which may wait for the display.
+            self.performance.end_frame(stats);
             target.finish().unwrap();
         }
     }
 
-    fn draw_object(&self, target: &mut Frame, model: &Model) {
-        target.clear_color_and_depth((0.0, 0.0, 1.0, 1.0), 1.0);
+    /// Renders the complete scene into the target.
+    ///
+    /// # Arguments
+    ///
+    /// * 'display' - The display, which owns the target.
+    /// * 'target' - The surface, where we render upon. This is the window or an offscreen buffer.
+    /// * 'model' - The model to render.
+    /// * 'viewport' - The part of the picture, which is rendered into the target.
+    /// * 'options' - Decides, whether the HUD is rendered and the background is transparent.
+    ///
+    /// Returns the draw calls, which were needed.
+    pub fn render<S: Surface>(&mut self, display: &Display, target: &mut S, model: &Model,
+                              viewport: &Viewport, options: &RenderOptions) -> DrawStats {
+        let background = if options.transparent { (0.0, 0.0, 0.0, 0.0) } else { (0.0, 0.0, 1.0, 1.0) };
+        target.clear_color_and_depth(background, 1.0);
+
+        let mut stats = DrawStats::default();
+        let lights = model.lighting.uniforms(&model.view_direction, &model.up, &model.view_position);
+        let shadow = self.shadow.draw(display, model, &self.positions, &self.indices, &lights, &mut stats);
+        self.draw_object(target, model, viewport, &lights, shadow, &mut stats);
+        self.reference.draw(display, target, model, viewport, &mut stats);
+        self.dimensions.draw(display, target, &mut self.hud, model, viewport, &mut stats);
+        self.highlight.draw(display, target, model, viewport, &mut stats);
+        self.annotations.draw(display, target, &mut self.hud, model, viewport, &mut stats);
+        if options.hud {
+            self.hud.draw(target, display, model, viewport, &self.performance, &mut stats);
+        } else {
+            self.hud.discard();
+        }
+        stats
+    }
+
+    /// Renders the scene offscreen into an image, which is a multiple of the window size. If the
+    /// image is larger than the graphics card can render at once, it is rendered in tiles. The
+    /// result are the RGBA pixels of the image, starting at the top left corner.
+    ///
+    /// # Arguments
+    ///
+    /// * 'display' - The display to render with.
+    /// * 'model' - The model to render.
+    /// * 'multiple' - The size of the image, compared to the window.
+    /// * 'options' - Decides, whether the HUD is rendered and the background is transparent.
+    pub fn render_image(&mut self, display: &Display, model: &Model, multiple: u32,
+                        options: &RenderOptions) -> Result<(u32, u32, Vec<u8>), Box<dyn std::error::Error>> {
+        let (window_width, window_height) = display.get_framebuffer_dimensions();
+        self.render_image_sized(display, model, window_width * multiple, window_height * multiple,
+                                multiple as f32 * scale_factor(display), options)
+    }
+
+    /// Renders the scene offscreen into an image of the provided size.
+    ///
+    /// # Arguments
+    ///
+    /// * 'display' - The display to render with.
+    /// * 'model' - The model to render.
+    /// * 'width', 'height' - The size of the image in pixels.
+    /// * 'scale' - The scale of the HUD and the annotations.
+    /// * 'options' - Decides, whether the HUD is rendered and the background is transparent.
+    pub fn render_image_sized(&mut self, display: &Display, model: &Model, width: u32, height: u32,
+                              scale: f32, options: &RenderOptions)
+        -> Result<(u32, u32, Vec<u8>), Box<dyn std::error::Error>> {
+        if width as u64 * height as u64 > MAX_IMAGE_PIXELS {
+            return Err(format!("The image of {width}x{height} pixels is larger than {MAX_IMAGE_PIXELS} pixels").into());
+        }
+        let (max_width, max_height) = display.get_max_viewport_dimensions();
+        let tile_width = width.min(max_width).min(MAX_TILE_SIZE);
+        let tile_height = height.min(max_height).min(MAX_TILE_SIZE);
+
+        let color = Texture2d::empty_with_format(display, UncompressedFloatFormat::U8U8U8U8,
+                                                 MipmapsOption::NoMipmap, tile_width, tile_height)?;
+        // The window has a depth buffer of 24 bits, so the images are rendered, as they are shown.
+        let depth = DepthRenderBuffer::new(display, DepthFormat::I24, tile_width, tile_height)?;
+        let mut image = vec![0u8; width as usize * height as usize * 4];
+
+        for bottom in (0..height).step_by(tile_height as usize) {
+            for left in (0..width).step_by(tile_width as usize) {
+                let mut target = SimpleFrameBuffer::with_depth_buffer(display, &color, &depth)?;
+                let viewport = Viewport::tile(width, height, scale, left, bottom, tile_width, tile_height);
+                self.render(display, &mut target, model, &viewport, options);
+
+                // The rows of the tile start at the bottom, the rows of the image at the top.
+                let pixels: RawImage2d<u8> = color.read();
+                let columns = tile_width.min(width - left) as usize;
+                for row in 0..tile_height.min(height - bottom) {
+                    let source = row as usize * tile_width as usize * 4;
+                    let y = height - 1 - (bottom + row);
+                    let destination = (y as usize * width as usize + left as usize) * 4;
+                    image[destination..destination + columns * 4]
+                        .copy_from_slice(&pixels.data[source..source + columns * 4]);
+                }
+            }
+        }
+        Ok((width, height, image))
+    }
 
-        let model_matrix = model_matrix(&model);
+    /// Draws the object and, if it is shown, the ground plane below it. Both are lit by the
+    /// lights and shadowed by the key light.
+    ///
+    /// # Arguments
+    ///
+    /// * 'target' - The surface, where we render upon.
+    /// * 'model' - The model to draw.
+    /// * 'viewport' - The part of the picture, which is rendered into the target.
+    /// * 'lights' - The lights in world coordinates.
+    /// * 'shadow' - The transformation into the shadow map, if the key light casts shadows.
+    /// * 'stats' - Counts the draw calls.
+    fn draw_object<S: Surface>(&self, target: &mut S, model: &Model, viewport: &Viewport, lights: &LightUniforms,
+                               shadow: Option<[[f32; 4]; 4]>, stats: &mut DrawStats) {
+        let model_matrix = model_matrix(model);
         let view = view_matrix(&model.view_position,
                                &model.view_direction,
                                &model.up);
-        let (width, height) = target.get_dimensions();
-        let perspective = perspective_matrix(width, height);
+        let perspective = viewport.projection(model);
         let offset: [f32; 3] = model.object_position.into();
-
-        let light = [1.4, 0.4, -0.7f32];
+        let shadow_mode = match shadow {
+            None => 0,
+            Some(_) if model.soft_shadows => 2,
+            Some(_) => 1,
+        };
+        let shadow_matrix = shadow.unwrap_or_else(|| Matrix4::identity().into());
 
         let params = glium::DrawParameters {
-            depth: glium::Depth {
-                test: glium::draw_parameters::DepthTest::IfLess,
-                write: true,
-                .. Default::default()
-            },
+            depth: depth_test(),
             //backface_culling: glium::draw_parameters::BackfaceCullingMode::CullClockWise,
+            time_elapsed_query: self.performance.query(),
             .. Default::default()
         };
 
         target.draw(&self.positions,
                     &self.indices,
                     &self.program,
-                    &uniform! { model: model_matrix, offset: offset, view: view, perspective: perspective, u_light: light },
+                    &WithLights {
+                        uniforms: uniform! { model: model_matrix, offset: offset, view: view, perspective: perspective,
+                                             u_eye: model.view_position, u_color: OBJECT_COLOR,
+                                             u_shadow_matrix: shadow_matrix, u_shadow_mode: shadow_mode,
+                                             u_shadow_map: self.shadow.sampled() },
+                        lights,
+                    },
                     &params).unwrap();
+        stats.count(self.indices.len() / 3);
+
+        if model.show_ground {
+            let (center, half_size) = model.ground_plane();
+            let ground_matrix: [[f32; 4]; 4] = (Matrix4::new_translation(&center)
+                * Matrix4::new_nonuniform_scaling(&Vector3::new(half_size, 1.0, half_size))).into();
+            let params = glium::DrawParameters { depth: depth_test(), .. Default::default() };
+            let (vertices, indices) = &self.ground;
+            target.draw(vertices,
+                        indices,
+                        &self.program,
+                        &WithLights {
+                            uniforms: uniform! { model: ground_matrix, offset: [0.0f32; 3], view: view,
+                                                 perspective: perspective, u_eye: model.view_position,
+                                                 u_color: GROUND_COLOR, u_shadow_matrix: shadow_matrix,
+                                                 u_shadow_mode: shadow_mode, u_shadow_map: self.shadow.sampled() },
+                            lights,
+                        },
+                        &params).unwrap();
+            stats.count(indices.len() / 3);
+        }
     }
 }
 
+/// The buffers of the ground plane: A square of edge length 2 around the origin, which faces up.
+fn ground_buffers(display: &Display) -> (VertexBuffer<Vertex>, IndexBuffer<u16>) {
+    let vertices: Vec<Vertex> = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]].iter()
+        .map(|[x, z]| Vertex { position: [*x, 0.0, *z], normal: [0.0, 1.0, 0.0] })
+        .collect();
+    (VertexBuffer::new(display, &vertices).unwrap(),
+     IndexBuffer::new(display, glium::index::PrimitiveType::TrianglesList, &[0u16, 1, 2, 0, 2, 3]).unwrap())
+}
+
+/// The scale factor of the window, which grows the HUD on HiDPI displays.
+fn scale_factor(display: &Display) -> f32 {
+    display.gl_window().window().scale_factor() as f32
+}
+
 /// Transformation of the model size and rotation to the OpenGL 1x1x1 box.
 fn model_matrix(model: &Model) -> [[f32; 4]; 4]
 {
     let rot = model.rot;
     let sf = model.scaling_factor;
     let mut final_matrix = Matrix4::from_euler_angles(rot[0], rot[1], rot[2]).append_scaling(sf);
-    final_matrix[(2, 3)] = 2.0;
+    final_matrix[(0, 3)] = OBJECT_HOME[0];
+    final_matrix[(1, 3)] = OBJECT_HOME[1];
+    final_matrix[(2, 3)] = OBJECT_HOME[2];
     log::trace!("The final matrix is: {final_matrix}");
     final_matrix.into()
 }
 
+/// The complete transformation of the object coordinates into the world coordinates, including
+/// the offset, which is applied in the vertex shader.
+pub fn world_matrix(model: &Model) -> Matrix4<f32>
+{
+    let model_matrix: Matrix4<f32> = model_matrix(model).into();
+    Matrix4::new_translation(&model.object_position) * model_matrix
+}
+
+/// Projects a point in world coordinates onto the screen. The result is given in pixels, with the
+/// origin in the top left corner, as it is used by the HUD. Points behind the viewer have no
+/// position on the screen.
+///
+/// # Arguments
+///
+/// * 'model' - The model, which provides the position and direction of the viewer.
+/// * 'point' - The point in world coordinates.
+/// * 'width' - The width of the target in pixels.
+/// * 'height' - The height of the target in pixels.
+pub fn project(model: &Model, point: &Vector3<f32>, width: u32, height: u32) -> Option<(f32, f32)>
+{
+    let view: Matrix4<f32> = view_matrix(&model.view_position, &model.view_direction, &model.up).into();
+    let projection: Matrix4<f32> = projection_matrix(model, width, height).into();
+    let clip = projection * view * Vector4::new(point[0], point[1], point[2], 1.0);
+    if clip[3] <= 0.0 {
+        return None;
+    }
+    let x = (clip[0] / clip[3] + 1.0) / 2.0 * width as f32;
+    let y = (1.0 - clip[1] / clip[3]) / 2.0 * height as f32;
+    Some((x, y))
+}
+
+/// The projection of the model, which is either the perspective or the orthographic projection.
+/// The orthographic projection shows the same extent at the distance of the target, as the
+/// perspective does.
+///
+/// # Arguments
+///
+/// * 'model' - The model, which provides the projection and the position of the viewer.
+/// * 'width', 'height' - The size of the picture in pixels.
+pub fn projection_matrix(model: &Model, width: u32, height: u32) -> [[f32; 4]; 4]
+{
+    let fov = model.lens.field_of_view;
+    let (znear, zfar) = model.clip_range();
+    match model.projection {
+        Projection::Perspective => perspective_matrix(width, height, fov, znear, zfar),
+        Projection::Orthographic => {
+            let half_height = model.target_distance() * (fov / 2.0).tan();
+            orthographic_matrix(width, height, half_height, znear, zfar)
+        },
+    }
+}
+
+/// The depth test of the scene: The nearer fragment wins.
+pub fn depth_test() -> glium::Depth
+{
+    glium::Depth { test: glium::draw_parameters::DepthTest::IfLess, write: true, .. Default::default() }
+}
+
+/// The depth test of geometry, which lies on the surface of the object, like the highlighted
+/// face. It also passes at the same depth and leaves the depth buffer, as it is.
+pub fn surface_depth_test() -> glium::Depth
+{
+    glium::Depth { test: glium::draw_parameters::DepthTest::IfLessOrEqual, write: false, .. Default::default() }
+}
+
 /// Giving all this a nice perspective.
-fn perspective_matrix(width: u32, height: u32) -> [[f32; 4]; 4]
+///
+/// # Arguments
+///
+/// * 'width', 'height' - The size of the picture in pixels.
+/// * 'fov' - The vertical field of view in RAD.
+/// * 'znear', 'zfar' - The distance of the near and the far plane.
+pub fn perspective_matrix(width: u32, height: u32, fov: f32, znear: f32, zfar: f32) -> [[f32; 4]; 4]
 {
     let aspect_ratio = height as f32 / width as f32;
 
-    let fov: f32 = std::f32::consts::PI / 3.0;
-    let zfar = 1024.0;
-    let znear = 0.1;
-
     let f = 1.0 / (fov / 2.0).tan();
 
     [
@@ -101,8 +476,30 @@ fn perspective_matrix(width: u32, height: u32) -> [[f32; 4]; 4]
     ]
 }
 
+/// The orthographic projection, which shows the provided height above and below the line of
+/// sight. As there is no perspective, the near plane may lie behind the viewer.
+///
+/// # Arguments
+///
+/// * 'width', 'height' - The size of the picture in pixels.
+/// * 'half_height' - The height, which is visible above the line of sight.
+/// * 'znear', 'zfar' - The distance of the near and the far plane.
+pub fn orthographic_matrix(width: u32, height: u32, half_height: f32, znear: f32, zfar: f32) -> [[f32; 4]; 4]
+{
+    let aspect_ratio = height as f32 / width as f32;
+
+    let f = 1.0 / half_height;
+
+    [
+        [f * aspect_ratio,    0.0,              0.0              ,   0.0],
+        [       0.0      ,     f ,              0.0              ,   0.0],
+        [       0.0      ,    0.0,        2.0/(zfar-znear)       ,   0.0],
+        [       0.0      ,    0.0,  -(zfar+znear)/(zfar-znear)   ,   1.0],
+    ]
+}
+
 /// The POV on the model.
-fn view_matrix(position: &[f32; 3], direction: &[f32; 3], up: &[f32; 3]) -> [[f32; 4]; 4] {
+pub fn view_matrix(position: &[f32; 3], direction: &[f32; 3], up: &[f32; 3]) -> [[f32; 4]; 4] {
     let f = {
         let f = direction;
         let len = f[0] * f[0] + f[1] * f[1] + f[2] * f[2];